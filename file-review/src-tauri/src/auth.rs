@@ -0,0 +1,120 @@
+//! Bearer-token auth and operator-approval gating for the web server.
+//!
+//! The web/tunnel server is reachable by anyone with the URL, so when auth
+//! is enabled every `/api/*` request must carry a token minted at startup, and writes to
+//! paths outside the reviewed file require an explicit operator decision.
+
+use rand::RngCore;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+/// Generate a random high-entropy bearer token (32 bytes, hex-encoded).
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The operator's decision on a pending out-of-allowlist write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Approved,
+    Denied,
+}
+
+/// Why a pending approval did not resolve to `Approved`.
+#[derive(Debug)]
+pub enum ApprovalError {
+    Denied,
+    Timeout,
+    Cancelled,
+}
+
+/// A pending approval as surfaced to the desktop UI, so the operator has
+/// something to act on besides a `tracing::info!` line to stderr.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingApproval {
+    pub id: String,
+    pub path: String,
+}
+
+struct PendingEntry {
+    path: PathBuf,
+    tx: oneshot::Sender<ApprovalDecision>,
+}
+
+/// Tracks writes outside the allowlist that are blocked on operator approval.
+#[derive(Default)]
+pub struct ApprovalRegistry {
+    pending: Mutex<HashMap<String, PendingEntry>>,
+}
+
+impl ApprovalRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new pending approval for a write to `path`, returning its
+    /// id and receiver.
+    pub fn request(&self, path: &Path) -> (String, oneshot::Receiver<ApprovalDecision>) {
+        let id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(
+            id.clone(),
+            PendingEntry {
+                path: path.to_path_buf(),
+                tx,
+            },
+        );
+        (id, rx)
+    }
+
+    /// Resolve a pending approval with the operator's decision. Returns
+    /// `false` if `id` is unknown or was already resolved.
+    pub fn resolve(&self, id: &str, decision: ApprovalDecision) -> bool {
+        match self.pending.lock().unwrap().remove(id) {
+            Some(entry) => entry.tx.send(decision).is_ok(),
+            None => false,
+        }
+    }
+
+    /// List every approval still awaiting an operator decision, for the
+    /// desktop UI to poll or render.
+    pub fn list(&self) -> Vec<PendingApproval> {
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, entry)| PendingApproval {
+                id: id.clone(),
+                path: entry.path.to_string_lossy().to_string(),
+            })
+            .collect()
+    }
+
+    /// Block until the approval resolves or `timeout_secs` elapses.
+    pub async fn wait(
+        &self,
+        id: &str,
+        rx: oneshot::Receiver<ApprovalDecision>,
+        timeout_secs: u64,
+    ) -> Result<(), ApprovalError> {
+        match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), rx).await {
+            Ok(Ok(ApprovalDecision::Approved)) => Ok(()),
+            Ok(Ok(ApprovalDecision::Denied)) => Err(ApprovalError::Denied),
+            Ok(Err(_)) => Err(ApprovalError::Cancelled),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(id);
+                Err(ApprovalError::Timeout)
+            }
+        }
+    }
+}
+
+/// True if `path` is `root` or a descendant of it, for any root in `allowlist`.
+pub fn is_allowlisted(path: &Path, allowlist: &[PathBuf]) -> bool {
+    allowlist.iter().any(|root| path.starts_with(root))
+}
@@ -1,7 +1,184 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+use std::path::PathBuf;
 use uuid::Uuid;
 
+/// Comment-delimiter syntax for a reviewed file's language, so review
+/// markers read as native comments instead of always being HTML comments.
+///
+/// `close` is empty for line-comment-only languages (Python, shell, SQL):
+/// the marker then runs to the end of its line instead of a literal closing
+/// token, and its regexes are anchored to line boundaries with `(?m)^...$`.
+#[derive(Debug, Clone, Copy)]
+pub struct CommentSyntax {
+    pub open: &'static str,
+    pub close: &'static str,
+}
+
+const HTML_COMMENT: CommentSyntax = CommentSyntax { open: "<!--", close: "-->" };
+const C_LIKE_COMMENT: CommentSyntax = CommentSyntax { open: "/*", close: "*/" };
+const HASH_COMMENT: CommentSyntax = CommentSyntax { open: "#", close: "" };
+const DASH_COMMENT: CommentSyntax = CommentSyntax { open: "--", close: "" };
+
+/// Resolve the comment syntax for a reviewed file from its extension,
+/// falling back to HTML comments (the original hardcoded behavior) when the
+/// extension is unknown or no file name is available at all (e.g. stdin
+/// content with no path).
+pub fn syntax_for_path(file_name: Option<&str>) -> CommentSyntax {
+    let ext = file_name
+        .and_then(|name| std::path::Path::new(name).extension())
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some(
+            "rs" | "c" | "h" | "cpp" | "hpp" | "cc" | "cs" | "java" | "go" | "swift" | "kt"
+            | "kts" | "scala" | "js" | "jsx" | "ts" | "tsx" | "css" | "scss",
+        ) => C_LIKE_COMMENT,
+        Some("py" | "sh" | "bash" | "zsh" | "rb" | "yaml" | "yml" | "toml" | "r" | "pl") => {
+            HASH_COMMENT
+        }
+        Some("sql" | "lua") => DASH_COMMENT,
+        _ => HTML_COMMENT,
+    }
+}
+
+const ID_CAPTURE: &str = r"([a-zA-Z0-9-]+)";
+
+fn inline_start_pattern(syntax: CommentSyntax) -> String {
+    let open = regex::escape(syntax.open);
+    if syntax.close.is_empty() {
+        format!(r"(?m)^{}\s*review-start\({}\)\s*$\n?", open, ID_CAPTURE)
+    } else {
+        format!(r"{}\s*review-start\({}\)\s*{}", open, ID_CAPTURE, regex::escape(syntax.close))
+    }
+}
+
+fn inline_start_removal_pattern(syntax: CommentSyntax, id: &str) -> String {
+    let open = regex::escape(syntax.open);
+    let escaped_id = regex::escape(id);
+    if syntax.close.is_empty() {
+        format!(r"(?m)^{}\s*review-start\({}\)\s*$\n?", open, escaped_id)
+    } else {
+        format!(r"{}\s*review-start\({}\)\s*{}", open, escaped_id, regex::escape(syntax.close))
+    }
+}
+
+fn inline_end_pattern(syntax: CommentSyntax, id: &str) -> String {
+    let open = regex::escape(syntax.open);
+    let escaped_id = regex::escape(id);
+    if syntax.close.is_empty() {
+        format!(r"(?m)^{}\s*review-end\({}\):\s*(.*)$\n?", open, escaped_id)
+    } else {
+        format!(
+            r"{}\s*review-end\({}\):\s*([\s\S]*?)\s*{}",
+            open,
+            escaped_id,
+            regex::escape(syntax.close)
+        )
+    }
+}
+
+fn line_start_pattern(syntax: CommentSyntax) -> String {
+    let open = regex::escape(syntax.open);
+    if syntax.close.is_empty() {
+        format!(r"(?m)^{}\s*review-line-start\({}\)\s*$\n?", open, ID_CAPTURE)
+    } else {
+        format!(
+            r"{}\s*review-line-start\({}\)\s*{}\n?",
+            open,
+            ID_CAPTURE,
+            regex::escape(syntax.close)
+        )
+    }
+}
+
+fn line_start_removal_pattern(syntax: CommentSyntax, id: &str) -> String {
+    let open = regex::escape(syntax.open);
+    let escaped_id = regex::escape(id);
+    if syntax.close.is_empty() {
+        format!(r"(?m)^{}\s*review-line-start\({}\)\s*$\n?", open, escaped_id)
+    } else {
+        format!(
+            r"{}\s*review-line-start\({}\)\s*{}\n?",
+            open,
+            escaped_id,
+            regex::escape(syntax.close)
+        )
+    }
+}
+
+fn line_end_pattern(syntax: CommentSyntax, id: &str) -> String {
+    let open = regex::escape(syntax.open);
+    let escaped_id = regex::escape(id);
+    if syntax.close.is_empty() {
+        format!(r"(?m)^{}\s*review-line-end\({}\):\s*(.*)$", open, escaped_id)
+    } else {
+        format!(
+            r"{}\s*review-line-end\({}\):\s*([\s\S]*?)\s*{}",
+            open,
+            escaped_id,
+            regex::escape(syntax.close)
+        )
+    }
+}
+
+fn line_end_removal_pattern(syntax: CommentSyntax, id: &str) -> String {
+    let open = regex::escape(syntax.open);
+    let escaped_id = regex::escape(id);
+    if syntax.close.is_empty() {
+        format!(r"(?m)\n?^{}\s*review-line-end\({}\):\s*.*$", open, escaped_id)
+    } else {
+        format!(
+            r"\n?{}\s*review-line-end\({}\):\s*[\s\S]*?\s*{}",
+            open,
+            escaped_id,
+            regex::escape(syntax.close)
+        )
+    }
+}
+
+/// Render a `review-start(id)` marker in `syntax`. Line-comment-only
+/// syntaxes (`close` empty) sit on their own line so the comment token
+/// covers the whole marker, matching how the parser anchors them.
+fn inline_start_marker(syntax: CommentSyntax, id: &str) -> String {
+    if syntax.close.is_empty() {
+        format!("\n{} review-start({})\n", syntax.open, id)
+    } else {
+        format!("{} review-start({}) {}", syntax.open, id, syntax.close)
+    }
+}
+
+/// Render a `review-end(id): text` marker in `syntax`.
+fn inline_end_marker(syntax: CommentSyntax, id: &str, text: &str) -> String {
+    if syntax.close.is_empty() {
+        format!("\n{} review-end({}): {}\n", syntax.open, id, text)
+    } else {
+        format!("{} review-end({}): {} {}", syntax.open, id, text, syntax.close)
+    }
+}
+
+/// Render a `review-line-start(id)` marker in `syntax`, terminated with a
+/// newline so it occupies its own line.
+fn line_start_marker(syntax: CommentSyntax, id: &str) -> String {
+    if syntax.close.is_empty() {
+        format!("{} review-line-start({})\n", syntax.open, id)
+    } else {
+        format!("{} review-line-start({}) {}\n", syntax.open, id, syntax.close)
+    }
+}
+
+/// Render a `review-line-end(id): text` marker in `syntax`, preceded by a
+/// newline so it occupies its own line.
+fn line_end_marker(syntax: CommentSyntax, id: &str, text: &str) -> String {
+    if syntax.close.is_empty() {
+        format!("\n{} review-line-end({}): {}", syntax.open, id, text)
+    } else {
+        format!("\n{} review-line-end({}): {} {}", syntax.open, id, text, syntax.close)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewComment {
     pub id: String,
@@ -24,6 +201,130 @@ pub struct OutputComment {
     pub start_line: usize,
     pub end_line: usize,
     pub content: String,
+    /// Byte offsets of the highlighted span in the source file, used by
+    /// [`render`] to cut the document into plain/highlighted runs.
+    pub byte_start: usize,
+    pub byte_end: usize,
+    /// Id of the tightest comment whose byte range properly contains this
+    /// one, set by [`build_comment_tree`]. `None` for a flat parse, or for a
+    /// top-level/conflicted comment.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Nesting depth within the containment tree, set by
+    /// [`build_comment_tree`]. `0` for a flat parse or a top-level comment.
+    #[serde(default)]
+    pub depth: usize,
+}
+
+/// Two comments whose byte ranges intersect without either containing the
+/// other, so they can't be placed in a single containment tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewConflict {
+    pub outer_id: String,
+    pub inner_id: String,
+}
+
+fn span_len(c: &OutputComment) -> usize {
+    c.byte_end.saturating_sub(c.byte_start)
+}
+
+/// Does `outer`'s byte range properly contain `inner`'s? Equal ranges don't
+/// count, so two comments spanning identical byte offsets are treated as
+/// siblings rather than one arbitrarily nesting inside the other. Keyed on
+/// `byte_start`/`byte_end` rather than `start_line`/`end_line` so that two
+/// comments nesting on the same line (e.g. a sentence-level highlight
+/// inside a paragraph highlight that doesn't cross a line boundary) are
+/// still correctly ordered instead of looking like an identical span.
+fn contains(outer: &OutputComment, inner: &OutputComment) -> bool {
+    outer.id != inner.id
+        && outer.byte_start <= inner.byte_start
+        && outer.byte_end >= inner.byte_end
+        && (outer.byte_start, outer.byte_end) != (inner.byte_start, inner.byte_end)
+}
+
+/// Arrange `comments` into a containment tree in place: each comment whose
+/// byte range is fully nested inside one or more other comments gets the
+/// tightest of them (by smallest span) as `parent_id`, and a `depth`
+/// counted from the roots. Comments that partially overlap —
+/// intersecting without either containing the other — can't be placed in
+/// the tree; they're left at `parent_id: None, depth: 0` and reported as
+/// [`ReviewConflict`] pairs instead.
+pub fn build_comment_tree(comments: &mut [OutputComment]) -> Vec<ReviewConflict> {
+    let mut conflicts = Vec::new();
+
+    for i in 0..comments.len() {
+        for j in (i + 1)..comments.len() {
+            let a = &comments[i];
+            let b = &comments[j];
+            let intersects = a.byte_start < b.byte_end && b.byte_start < a.byte_end;
+            if !intersects {
+                continue;
+            }
+            if !contains(a, b) && !contains(b, a) {
+                conflicts.push(ReviewConflict {
+                    outer_id: a.id.clone(),
+                    inner_id: b.id.clone(),
+                });
+            }
+        }
+    }
+
+    let conflicted: std::collections::HashSet<&str> = conflicts
+        .iter()
+        .flat_map(|c| [c.outer_id.as_str(), c.inner_id.as_str()])
+        .collect();
+
+    let snapshot = comments.to_vec();
+    for c in comments.iter_mut() {
+        if conflicted.contains(c.id.as_str()) {
+            continue;
+        }
+        c.parent_id = snapshot
+            .iter()
+            .filter(|other| !conflicted.contains(other.id.as_str()) && contains(other, c))
+            .min_by_key(|other| span_len(other))
+            .map(|parent| parent.id.clone());
+    }
+
+    // Depths are derived after every parent_id is set, by walking each
+    // comment's ancestor chain (cycle-free since a parent's span is always
+    // strictly larger than its child's).
+    let parent_of: std::collections::HashMap<&str, Option<&str>> = comments
+        .iter()
+        .map(|c| (c.id.as_str(), c.parent_id.as_deref()))
+        .collect();
+
+    let depths: Vec<usize> = comments
+        .iter()
+        .map(|c| {
+            let mut depth = 0;
+            let mut current = c.parent_id.as_deref();
+            while let Some(id) = current {
+                depth += 1;
+                current = parent_of.get(id).copied().flatten();
+            }
+            depth
+        })
+        .collect();
+
+    for (c, depth) in comments.iter_mut().zip(depths) {
+        c.depth = depth;
+    }
+
+    conflicts
+}
+
+/// Parse comments and arrange them into a containment tree (see
+/// [`build_comment_tree`]) in one step, for the frontend's nested-comment
+/// view.
+#[tauri::command]
+pub fn parse_comments_tree(
+    content: String,
+    file_name: Option<String>,
+) -> (Vec<OutputComment>, Vec<ReviewConflict>) {
+    let mut comments = parse_comments_for_output(&content, file_name.as_deref());
+    let conflicts = build_comment_tree(&mut comments);
+    (comments, conflicts)
 }
 
 /// Calculate line number from byte position
@@ -63,22 +364,23 @@ fn byte_offset_to_char_offset(content: &str, byte_pos: usize) -> usize {
         .sum()
 }
 
-/// Parse comments and return OutputComment structs with line numbers
-/// Uses byte positions internally for accurate string slicing
-pub fn parse_comments_for_output(content: &str) -> Vec<OutputComment> {
+/// Parse comments and return OutputComment structs with line numbers.
+/// Uses byte positions internally for accurate string slicing. `file_name`
+/// selects the comment syntax the markers were written in (see
+/// [`syntax_for_path`]); pass `None` to assume HTML comments.
+pub fn parse_comments_for_output(content: &str, file_name: Option<&str>) -> Vec<OutputComment> {
+    let syntax = syntax_for_path(file_name);
     let mut comments = Vec::new();
 
     // Parse inline wrapped comments
-    let inline_start_re = Regex::new(r"<!--\s*review-start\(([a-zA-Z0-9-]+)\)\s*-->").unwrap();
-    let inline_end_template = r"<!--\s*review-end\(ID\):\s*([\s\S]*?)\s*-->";
+    let inline_start_re = Regex::new(&inline_start_pattern(syntax)).unwrap();
 
     for start_cap in inline_start_re.captures_iter(content) {
         let id = start_cap.get(1).map_or("", |m| m.as_str()).to_string();
         let start_match = start_cap.get(0).unwrap();
         let byte_content_start = start_match.end();
 
-        let end_pattern = inline_end_template.replace("ID", &regex::escape(&id));
-        let end_re = Regex::new(&end_pattern).unwrap();
+        let end_re = Regex::new(&inline_end_pattern(syntax, &id)).unwrap();
 
         if let Some(end_cap) = end_re.captures(&content[byte_content_start..]) {
             let comment_text = end_cap.get(1).map_or("", |m| m.as_str()).to_string();
@@ -99,21 +401,23 @@ pub fn parse_comments_for_output(content: &str) -> Vec<OutputComment> {
                 start_line,
                 end_line,
                 content: highlighted_content,
+                byte_start: byte_content_start,
+                byte_end: byte_content_end,
+                parent_id: None,
+                depth: 0,
             });
         }
     }
 
     // Parse line comments
-    let line_start_re = Regex::new(r"<!--\s*review-line-start\(([a-zA-Z0-9-]+)\)\s*-->\n?").unwrap();
-    let line_end_template = r"<!--\s*review-line-end\(ID\):\s*([\s\S]*?)\s*-->";
+    let line_start_re = Regex::new(&line_start_pattern(syntax)).unwrap();
 
     for start_cap in line_start_re.captures_iter(content) {
         let id = start_cap.get(1).map_or("", |m| m.as_str()).to_string();
         let start_match = start_cap.get(0).unwrap();
         let byte_content_start = start_match.end();
 
-        let end_pattern = line_end_template.replace("ID", &regex::escape(&id));
-        let end_re = Regex::new(&end_pattern).unwrap();
+        let end_re = Regex::new(&line_end_pattern(syntax, &id)).unwrap();
 
         if let Some(end_cap) = end_re.captures(&content[byte_content_start..]) {
             let comment_text = end_cap.get(1).map_or("", |m| m.as_str()).to_string();
@@ -140,6 +444,10 @@ pub fn parse_comments_for_output(content: &str) -> Vec<OutputComment> {
                 start_line,
                 end_line,
                 content: highlighted_content,
+                byte_start: byte_content_start,
+                byte_end: byte_content_end,
+                parent_id: None,
+                depth: 0,
             });
         }
     }
@@ -147,13 +455,229 @@ pub fn parse_comments_for_output(content: &str) -> Vec<OutputComment> {
     comments
 }
 
-/// Format comments as human-readable string
-pub fn format_comments_readable(comments: &[OutputComment]) -> String {
-    if comments.is_empty() {
-        return String::from("No review comments found.");
+/// Rendering callbacks for walking a reviewed document alongside its parsed
+/// comments, in the style of orgize's `Render`/`HtmlHandler`. [`render`]
+/// drives these in document order; every method defaults to a no-op (or,
+/// for `highlighted_text`, to writing the text through unchanged) so a
+/// handler only needs to override what it cares about.
+pub trait ReviewHandler {
+    fn document_start<W: Write>(&mut self, _w: &mut W) -> fmt::Result {
+        Ok(())
+    }
+    fn document_end<W: Write>(&mut self, _w: &mut W) -> fmt::Result {
+        Ok(())
     }
+    fn comment_start<W: Write>(&mut self, _w: &mut W, _comment: &OutputComment) -> fmt::Result {
+        Ok(())
+    }
+    fn highlighted_text<W: Write>(&mut self, w: &mut W, text: &str) -> fmt::Result {
+        w.write_str(text)
+    }
+    fn comment_annotation<W: Write>(&mut self, _w: &mut W, _comment: &OutputComment) -> fmt::Result {
+        Ok(())
+    }
+    fn comment_end<W: Write>(&mut self, _w: &mut W, _comment: &OutputComment) -> fmt::Result {
+        Ok(())
+    }
+}
 
-    let mut output = format!("=== Review Comments ({}) ===\n", comments.len());
+/// Walk `content` alongside `comments`, cutting it at each comment's byte
+/// boundaries and driving `handler`'s callbacks in document order: plain and
+/// highlighted text both go through `highlighted_text`, with
+/// `comment_start`/`comment_annotation`/`comment_end` bracketing each
+/// highlighted span. An end boundary at the same offset as a start boundary
+/// closes before the next one opens, so adjacent spans don't interleave.
+pub fn render<H: ReviewHandler, W: Write>(
+    handler: &mut H,
+    w: &mut W,
+    content: &str,
+    comments: &[OutputComment],
+) -> fmt::Result {
+    enum Edge<'a> {
+        Start(&'a OutputComment),
+        End(&'a OutputComment),
+    }
+
+    let mut events: Vec<(usize, u8, Edge)> = Vec::with_capacity(comments.len() * 2);
+    for c in comments {
+        events.push((c.byte_start, 1, Edge::Start(c)));
+        events.push((c.byte_end, 0, Edge::End(c)));
+    }
+    events.sort_by_key(|(pos, priority, _)| (*pos, *priority));
+
+    handler.document_start(w)?;
+
+    let mut cursor = 0;
+    for (pos, _, edge) in events {
+        let pos = pos.min(content.len());
+        if pos > cursor {
+            handler.highlighted_text(w, &content[cursor..pos])?;
+            cursor = pos;
+        }
+        match edge {
+            Edge::Start(c) => handler.comment_start(w, c)?,
+            Edge::End(c) => {
+                handler.comment_annotation(w, c)?;
+                handler.comment_end(w, c)?;
+            }
+        }
+    }
+    if cursor < content.len() {
+        handler.highlighted_text(w, &content[cursor..])?;
+    }
+
+    handler.document_end(w)
+}
+
+/// Escape the five characters that matter inside HTML text/attribute
+/// content; review comments are free-form text, not markup.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// [`ReviewHandler`] backing [`format_comments_readable`]: ignores the
+/// walked document text (each comment already carries its own highlighted
+/// `content`) and emits the same entry format `format_comment_entries`
+/// builds by hand, each entry complete at its comment's start.
+struct ReadableHandler;
+
+impl ReviewHandler for ReadableHandler {
+    fn highlighted_text<W: Write>(&mut self, _w: &mut W, _text: &str) -> fmt::Result {
+        Ok(())
+    }
+
+    fn comment_start<W: Write>(&mut self, w: &mut W, comment: &OutputComment) -> fmt::Result {
+        let line_info = if comment.start_line == comment.end_line {
+            format!("Line {}", comment.start_line)
+        } else {
+            format!("Lines {}-{}", comment.start_line, comment.end_line)
+        };
+        write!(w, "\n[{}] {} ({}):\n", comment.id, line_info, comment.comment_type)?;
+        if comment.content.is_empty() {
+            w.write_str("    (empty selection)\n")?;
+        } else {
+            for line in comment.content.lines() {
+                write!(w, "    \"{}\"\n", line)?;
+            }
+        }
+        write!(w, "    → {}\n", comment.comment)
+    }
+}
+
+/// [`ReviewHandler`] backing [`format_comments_json`]: uses the walk purely
+/// to collect comments in document order, then serializes them with
+/// `serde_json` at `document_end` (preserving pretty-printing, which a
+/// purely incremental writer can't do).
+#[derive(Default)]
+struct JsonHandler {
+    ordered: Vec<OutputComment>,
+}
+
+impl JsonHandler {
+    fn into_comments(self) -> Vec<OutputComment> {
+        self.ordered
+    }
+}
+
+impl ReviewHandler for JsonHandler {
+    fn highlighted_text<W: Write>(&mut self, _w: &mut W, _text: &str) -> fmt::Result {
+        Ok(())
+    }
+
+    fn comment_start<W: Write>(&mut self, _w: &mut W, comment: &OutputComment) -> fmt::Result {
+        self.ordered.push(comment.clone());
+        Ok(())
+    }
+
+    fn document_end<W: Write>(&mut self, w: &mut W) -> fmt::Result {
+        let json = serde_json::to_string_pretty(&self.ordered).unwrap_or_else(|_| "[]".to_string());
+        w.write_str(&json)
+    }
+}
+
+/// Renders the reviewed document as standalone, shareable HTML: every
+/// highlighted span is wrapped in `<mark data-review-id="…" title="…">`
+/// with a numbered footnote reference next to it, and the full comment
+/// texts are listed at the end as a numbered footnote list. Source text
+/// outside any span is HTML-escaped and passed through unchanged.
+///
+/// Line comments highlight a whole block, where an inline footnote marker
+/// would read oddly right after a newline, so their reference is rendered
+/// as its own block underneath instead of inline like an inline comment's.
+#[derive(Default)]
+pub struct HtmlHandler {
+    footnotes: Vec<String>,
+}
+
+impl HtmlHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReviewHandler for HtmlHandler {
+    fn document_start<W: Write>(&mut self, w: &mut W) -> fmt::Result {
+        w.write_str(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"></head>\n<body>\n<pre class=\"review-document\">\n",
+        )
+    }
+
+    fn highlighted_text<W: Write>(&mut self, w: &mut W, text: &str) -> fmt::Result {
+        w.write_str(&escape_html(text))
+    }
+
+    fn comment_start<W: Write>(&mut self, w: &mut W, comment: &OutputComment) -> fmt::Result {
+        write!(
+            w,
+            "<mark data-review-id=\"{}\" title=\"{}\">",
+            escape_html(&comment.id),
+            escape_html(&comment.comment)
+        )
+    }
+
+    fn comment_annotation<W: Write>(&mut self, w: &mut W, comment: &OutputComment) -> fmt::Result {
+        self.footnotes.push(comment.comment.clone());
+        let n = self.footnotes.len();
+        if comment.comment_type == "line" {
+            write!(
+                w,
+                "</mark>\n<div class=\"review-annotation\">[<a href=\"#review-note-{n}\">{n}</a>]</div>\n"
+            )
+        } else {
+            write!(w, "</mark><sup><a href=\"#review-note-{n}\">[{n}]</a></sup>")
+        }
+    }
+
+    fn document_end<W: Write>(&mut self, w: &mut W) -> fmt::Result {
+        w.write_str("</pre>\n")?;
+        if !self.footnotes.is_empty() {
+            w.write_str("<ol class=\"review-notes\">\n")?;
+            for text in &self.footnotes {
+                write!(w, "<li>{}</li>\n", escape_html(text))?;
+            }
+            w.write_str("</ol>\n")?;
+        }
+        w.write_str("</body>\n</html>\n")
+    }
+}
+
+/// Render `content` and `comments` as a standalone HTML export (see
+/// [`HtmlHandler`]).
+pub fn format_comments_html(content: &str, comments: &[OutputComment]) -> String {
+    let mut handler = HtmlHandler::new();
+    let mut output = String::new();
+    let _ = render(&mut handler, &mut output, content, comments);
+    output
+}
+
+/// Render each comment's line range, content, and annotation; used by the
+/// multi-file readable formatter.
+fn format_comment_entries(comments: &[OutputComment]) -> String {
+    let mut output = String::new();
 
     for c in comments {
         let line_info = if c.start_line == c.end_line {
@@ -178,9 +702,71 @@ pub fn format_comments_readable(comments: &[OutputComment]) -> String {
     output
 }
 
+/// Format comments as human-readable string
+pub fn format_comments_readable(content: &str, comments: &[OutputComment]) -> String {
+    if comments.is_empty() {
+        return String::from("No review comments found.");
+    }
+
+    let mut output = format!("=== Review Comments ({}) ===\n", comments.len());
+    let _ = render(&mut ReadableHandler, &mut output, content, comments);
+    output
+}
+
 /// Format comments as JSON string
-pub fn format_comments_json(comments: &[OutputComment]) -> String {
-    serde_json::to_string_pretty(comments).unwrap_or_else(|_| "[]".to_string())
+pub fn format_comments_json(content: &str, comments: &[OutputComment]) -> String {
+    let mut output = String::new();
+    let _ = render(&mut JsonHandler::default(), &mut output, content, comments);
+    output
+}
+
+/// One file's comments, for the aggregated multi-file session output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileComments {
+    pub file: String,
+    pub comments: Vec<OutputComment>,
+}
+
+/// Format comments from every file in a multi-file/directory review
+/// session as human-readable string, one section per file.
+pub fn format_comments_readable_multi(files: &[FileComments]) -> String {
+    let total: usize = files.iter().map(|f| f.comments.len()).sum();
+    if total == 0 {
+        return String::from("No review comments found.");
+    }
+
+    let mut output = format!("=== Review Comments ({} files, {} total) ===\n", files.len(), total);
+
+    for f in files {
+        if f.comments.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n--- {} ({}) ---\n", f.file, f.comments.len()));
+        output.push_str(&format_comment_entries(&f.comments));
+    }
+
+    output
+}
+
+/// Format comments from every file in a multi-file/directory review
+/// session as JSON, keyed by path.
+pub fn format_comments_json_multi(files: &[FileComments]) -> String {
+    serde_json::to_string_pretty(files).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Read and parse comments for every file in a session's file queue,
+/// skipping any that can no longer be read. Shared by the native and web
+/// `quit`/close handlers.
+pub fn collect_file_comments(files: &[PathBuf]) -> Vec<FileComments> {
+    files
+        .iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            let file = path.to_string_lossy().to_string();
+            let comments = parse_comments_for_output(&content, Some(&file));
+            Some(FileComments { file, comments })
+        })
+        .collect()
 }
 
 /// Combined output for stdin mode (file path + content + comments)
@@ -199,10 +785,14 @@ pub fn format_stdin_output_json(
     comments: &[OutputComment],
     modified: bool,
 ) -> String {
+    let mut discard = String::new();
+    let mut handler = JsonHandler::default();
+    let _ = render(&mut handler, &mut discard, content, comments);
+
     let output = StdinOutput {
         file: file.to_string(),
         content: content.to_string(),
-        comments: comments.to_vec(),
+        comments: handler.into_comments(),
         modified,
     };
     serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
@@ -239,36 +829,19 @@ pub fn format_stdin_output_readable(
     if comments.is_empty() {
         output.push_str("No review comments.\n");
     } else {
-        for c in comments {
-            let line_info = if c.start_line == c.end_line {
-                format!("Line {}", c.start_line)
-            } else {
-                format!("Lines {}-{}", c.start_line, c.end_line)
-            };
-
-            output.push_str(&format!("\n[{}] {} ({}):\n", c.id, line_info, c.comment_type));
-
-            if c.content.is_empty() {
-                output.push_str("    (empty selection)\n");
-            } else {
-                for line in c.content.lines() {
-                    output.push_str(&format!("    \"{}\"\n", line));
-                }
-            }
-            output.push_str(&format!("    → {}\n", c.comment));
-        }
+        let _ = render(&mut ReadableHandler, &mut output, content, comments);
     }
 
     output
 }
 
 /// Internal parsing logic for Tauri command - returns character positions for frontend
-fn parse_comments_internal(content: &str) -> Vec<ReviewComment> {
+fn parse_comments_internal(content: &str, file_name: Option<&str>) -> Vec<ReviewComment> {
+    let syntax = syntax_for_path(file_name);
     let mut comments = Vec::new();
 
-    // Parse inline wrapped comments: <!-- review-start(id) -->...<!-- review-end(id): text -->
-    let inline_start_re = Regex::new(r"<!--\s*review-start\(([a-zA-Z0-9-]+)\)\s*-->").unwrap();
-    let inline_end_template = r"<!--\s*review-end\(ID\):\s*([\s\S]*?)\s*-->";
+    // Parse inline wrapped comments: e.g. <!-- review-start(id) -->...<!-- review-end(id): text -->
+    let inline_start_re = Regex::new(&inline_start_pattern(syntax)).unwrap();
 
     for start_cap in inline_start_re.captures_iter(content) {
         let id = start_cap.get(1).map_or("", |m| m.as_str()).to_string();
@@ -277,8 +850,7 @@ fn parse_comments_internal(content: &str) -> Vec<ReviewComment> {
         let byte_content_start = start_match.end();
 
         // Find matching end marker
-        let end_pattern = inline_end_template.replace("ID", &regex::escape(&id));
-        let end_re = Regex::new(&end_pattern).unwrap();
+        let end_re = Regex::new(&inline_end_pattern(syntax, &id)).unwrap();
 
         if let Some(end_cap) = end_re.captures(&content[byte_content_start..]) {
             let comment_text = end_cap.get(1).map_or("", |m| m.as_str()).to_string();
@@ -301,9 +873,8 @@ fn parse_comments_internal(content: &str) -> Vec<ReviewComment> {
         }
     }
 
-    // Parse line comments: <!-- review-line-start(id) -->\n...\n<!-- review-line-end(id): text -->
-    let line_start_re = Regex::new(r"<!--\s*review-line-start\(([a-zA-Z0-9-]+)\)\s*-->\n?").unwrap();
-    let line_end_template = r"<!--\s*review-line-end\(ID\):\s*([\s\S]*?)\s*-->";
+    // Parse line comments: e.g. <!-- review-line-start(id) -->\n...\n<!-- review-line-end(id): text -->
+    let line_start_re = Regex::new(&line_start_pattern(syntax)).unwrap();
 
     for start_cap in line_start_re.captures_iter(content) {
         let id = start_cap.get(1).map_or("", |m| m.as_str()).to_string();
@@ -312,8 +883,7 @@ fn parse_comments_internal(content: &str) -> Vec<ReviewComment> {
         let byte_content_start = start_match.end();
 
         // Find matching end marker
-        let end_pattern = line_end_template.replace("ID", &regex::escape(&id));
-        let end_re = Regex::new(&end_pattern).unwrap();
+        let end_re = Regex::new(&line_end_pattern(syntax, &id)).unwrap();
 
         if let Some(end_cap) = end_re.captures(&content[byte_content_start..]) {
             let comment_text = end_cap.get(1).map_or("", |m| m.as_str()).to_string();
@@ -345,8 +915,30 @@ fn parse_comments_internal(content: &str) -> Vec<ReviewComment> {
 }
 
 #[tauri::command]
-pub fn parse_comments(content: String) -> Vec<ReviewComment> {
-    parse_comments_internal(&content)
+pub fn parse_comments(content: String, file_name: Option<String>) -> Vec<ReviewComment> {
+    parse_comments_internal(&content, file_name.as_deref())
+}
+
+/// Parse `content` and render it as a standalone HTML document with every
+/// review comment shown as a `<mark>`-highlighted span and a trailing
+/// footnote list, so a review can be shared as a browser-viewable artifact.
+#[tauri::command]
+pub fn export_html(content: String, file_name: Option<String>) -> String {
+    let comments = parse_comments_for_output(&content, file_name.as_deref());
+    format_comments_html(&content, &comments)
+}
+
+/// Expand `[start, end)` out to the bounds of the whole lines it touches:
+/// `start` moves back to the start of its line, `end` moves forward to just
+/// before its line's trailing newline (or to end of content, if there isn't
+/// one), so the line-end marker can take that newline's place.
+fn snap_to_line_bounds(content: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = content[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = match content[end..].find('\n') {
+        Some(rel) => end + rel,
+        None => content.len(),
+    };
+    (line_start, line_end)
 }
 
 #[tauri::command]
@@ -355,10 +947,10 @@ pub fn insert_wrapped_comment(
     start_pos: usize,  // Character offset from frontend
     end_pos: usize,    // Character offset from frontend
     text: String,
+    file_name: Option<String>,
 ) -> (String, String) {
+    let syntax = syntax_for_path(file_name.as_deref());
     let id = Uuid::new_v4().to_string()[..8].to_string();
-    let start_marker = format!("<!-- review-start({}) -->", id);
-    let end_marker = format!("<!-- review-end({}): {} -->", id, text);
 
     // Convert character offsets to byte offsets for string slicing
     let byte_start = char_offset_to_byte_offset(&content, start_pos)
@@ -366,6 +958,29 @@ pub fn insert_wrapped_comment(
     let byte_end = char_offset_to_byte_offset(&content, end_pos)
         .unwrap_or(content.len());
 
+    // A line-comment-only syntax can only place a marker on its own line,
+    // so wrapping an arbitrary mid-line byte range would split a statement
+    // across lines with unremovable injected newlines. Snap the span out
+    // to its containing whole lines and fall back to the same line-marker
+    // shape `insert_nextline_comment` uses instead.
+    if syntax.close.is_empty() {
+        let (byte_start, byte_end) = snap_to_line_bounds(&content, byte_start, byte_end);
+        let start_marker = line_start_marker(syntax, &id);
+        let end_marker = line_end_marker(syntax, &id, &text);
+
+        let mut result = String::new();
+        result.push_str(&content[..byte_start]);
+        result.push_str(&start_marker);
+        result.push_str(&content[byte_start..byte_end]);
+        result.push_str(&end_marker);
+        result.push_str(&content[byte_end..]);
+
+        return (result, id);
+    }
+
+    let start_marker = inline_start_marker(syntax, &id);
+    let end_marker = inline_end_marker(syntax, &id, &text);
+
     let mut result = String::new();
     result.push_str(&content[..byte_start]);
     result.push_str(&start_marker);
@@ -382,10 +997,12 @@ pub fn insert_nextline_comment(
     line_start_pos: usize,  // Character offset from frontend
     line_end_pos: usize,    // Character offset from frontend
     text: String,
+    file_name: Option<String>,
 ) -> (String, String) {
+    let syntax = syntax_for_path(file_name.as_deref());
     let id = Uuid::new_v4().to_string()[..8].to_string();
-    let start_marker = format!("<!-- review-line-start({}) -->\n", id);
-    let end_marker = format!("\n<!-- review-line-end({}): {} -->", id, text);
+    let start_marker = line_start_marker(syntax, &id);
+    let end_marker = line_end_marker(syntax, &id, &text);
 
     // Convert character offsets to byte offsets for string slicing
     let byte_start = char_offset_to_byte_offset(&content, line_start_pos)
@@ -404,25 +1021,19 @@ pub fn insert_nextline_comment(
 }
 
 #[tauri::command]
-pub fn remove_comment(content: String, comment_id: String) -> String {
-    let escaped_id = regex::escape(&comment_id);
+pub fn remove_comment(content: String, comment_id: String, file_name: Option<String>) -> String {
+    let syntax = syntax_for_path(file_name.as_deref());
 
     // Remove inline wrapped comments (start and end markers)
-    let inline_start_pattern = format!(r"<!--\s*review-start\({}\)\s*-->", escaped_id);
-    let inline_end_pattern = format!(r"<!--\s*review-end\({}\):\s*[\s\S]*?\s*-->", escaped_id);
-
-    let inline_start_re = Regex::new(&inline_start_pattern).unwrap();
-    let inline_end_re = Regex::new(&inline_end_pattern).unwrap();
+    let inline_start_re = Regex::new(&inline_start_removal_pattern(syntax, &comment_id)).unwrap();
+    let inline_end_re = Regex::new(&inline_end_pattern(syntax, &comment_id)).unwrap();
 
     let result = inline_start_re.replace_all(&content, "");
     let result = inline_end_re.replace_all(&result, "");
 
     // Remove line comments (start marker with newline and end marker with preceding newline)
-    let line_start_pattern = format!(r"<!--\s*review-line-start\({}\)\s*-->\n?", escaped_id);
-    let line_end_pattern = format!(r"\n?<!--\s*review-line-end\({}\):\s*[\s\S]*?\s*-->", escaped_id);
-
-    let line_start_re = Regex::new(&line_start_pattern).unwrap();
-    let line_end_re = Regex::new(&line_end_pattern).unwrap();
+    let line_start_re = Regex::new(&line_start_removal_pattern(syntax, &comment_id)).unwrap();
+    let line_end_re = Regex::new(&line_end_removal_pattern(syntax, &comment_id)).unwrap();
 
     let result = line_start_re.replace_all(&result, "");
     let result = line_end_re.replace_all(&result, "");
@@ -1,7 +1,26 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs;
 use std::path::PathBuf;
 
+/// The schema version `load_config` migrates any stored config up to
+/// before deserializing it into [`AppConfig`]. Bump this and add a step to
+/// [`MIGRATIONS`] whenever a field is renamed, retyped, or needs a new
+/// non-serde-default default.
+const CURRENT_VERSION: u32 = 1;
+
+/// Ordered migration steps, keyed by the version each step migrates
+/// *from*. `load_config` applies them in order while the stored version is
+/// below `CURRENT_VERSION`, mutating the parsed `Value` in place and
+/// bumping the version after each step.
+const MIGRATIONS: &[(u32, fn(&mut Value))] = &[(0, migrate_v0_to_v1)];
+
+/// v0 is every config written before schema versioning existed; all of its
+/// fields already have `#[serde(default)]`, so this step has nothing to
+/// rewrite and exists only to carry the version forward and give later
+/// migrations a template to follow.
+fn migrate_v0_to_v1(_value: &mut Value) {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowConfig {
     pub width: u32,
@@ -10,13 +29,25 @@ pub struct WindowConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    #[serde(default)]
+    pub version: u32,
     pub theme: String,
     pub vim_mode: bool,
     #[serde(default = "default_font_size")]
     pub font_size: u32,
     #[serde(default)]
     pub markdown_raw: bool,
+    /// Allowed CORS origins for the web server's API. Empty means "allow
+    /// any origin" unless a tunnel is active, in which case only the
+    /// tunnel's own origin is added automatically.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
     pub window: WindowConfig,
+    /// Keys written by a newer build that this one doesn't know about yet,
+    /// preserved verbatim so a downgrade doesn't discard them on the next
+    /// save.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 fn default_font_size() -> u32 {
@@ -26,14 +57,17 @@ fn default_font_size() -> u32 {
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: CURRENT_VERSION,
             theme: "dark".to_string(),
             vim_mode: false,
             font_size: 14,
             markdown_raw: false,
+            cors_origins: Vec::new(),
             window: WindowConfig {
                 width: 1200,
                 height: 800,
             },
+            extra: Map::new(),
         }
     }
 }
@@ -44,21 +78,73 @@ pub fn get_config_path() -> PathBuf {
         .join(".file-reviewer.json")
 }
 
+/// Where an unparseable config gets backed up to, so a corrupted or
+/// pre-JSON config file is never lost without a recoverable copy.
+fn get_backup_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".file-reviewer.json.bak")
+}
+
+/// Walk `value`'s stored `version` field up to `CURRENT_VERSION` by
+/// applying each matching step in [`MIGRATIONS`] in order, stamping the
+/// final version back into `value` so the subsequent `AppConfig`
+/// deserialization picks it up.
+fn migrate(value: &mut Value) {
+    let mut version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+
+    while version < CURRENT_VERSION {
+        let Some((_, step)) = MIGRATIONS.iter().find(|(from, _)| *from == version) else {
+            break;
+        };
+        step(value);
+        version += 1;
+    }
+
+    if let Value::Object(map) = value {
+        map.insert("version".to_string(), Value::from(version));
+    }
+}
+
 #[tauri::command]
 pub fn load_config() -> AppConfig {
     let path = get_config_path();
-    if path.exists() {
-        match fs::read_to_string(&path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => AppConfig::default(),
+    if !path.exists() {
+        return AppConfig::default();
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) => return AppConfig::default(),
+    };
+
+    let mut value: Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(_) => {
+            // Not valid JSON at all: back up the original before falling
+            // back to defaults, rather than silently discarding it.
+            let _ = fs::write(get_backup_path(), &content);
+            return AppConfig::default();
+        }
+    };
+
+    migrate(&mut value);
+    match serde_json::from_value(value) {
+        Ok(config) => config,
+        Err(_) => {
+            // Valid JSON, but missing/mistyped a field `AppConfig` requires
+            // (e.g. hand-edited, or written by an unrelated tool): back up
+            // the original before falling back to defaults, for the same
+            // reason as the JSON-parse-failure case above.
+            let _ = fs::write(get_backup_path(), &content);
+            AppConfig::default()
         }
-    } else {
-        AppConfig::default()
     }
 }
 
 #[tauri::command]
-pub fn save_config(config: AppConfig) -> Result<(), String> {
+pub fn save_config(mut config: AppConfig) -> Result<(), String> {
+    config.version = CURRENT_VERSION;
     let path = get_config_path();
     let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
     fs::write(&path, content).map_err(|e| e.to_string())
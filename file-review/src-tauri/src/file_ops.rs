@@ -1,30 +1,130 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tauri::State;
 
 pub struct AppState {
     pub current_file: Mutex<Option<PathBuf>>,
+    /// The ordered queue of files in this review session (multiple file
+    /// arguments, or every reviewable file under a directory argument).
+    /// Holds a single entry for a plain single-file/stdin session, and is
+    /// empty when no file was given.
+    pub file_list: Mutex<Vec<PathBuf>>,
+    /// `current_file`'s index into `file_list`, moved by `next_file`/`prev_file`.
+    pub file_index: Mutex<usize>,
     pub silent: bool,
     pub json_output: bool,
     pub stdin_mode: bool,
     pub original_content: Mutex<Option<String>>,
+    /// When set, confines `read_file`/`write_file`/`set_current_file` to
+    /// this directory; `None` leaves paths unrestricted (native CLI use).
+    pub root_dir: Option<PathBuf>,
+}
+
+/// Recursively collect every file under `dir`, skipping hidden entries
+/// (dotfiles, `.git`, etc.), sorted for a stable review order.
+pub fn walk_reviewable_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_hidden = entry
+                .file_name()
+                .to_str()
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(false);
+            if is_hidden {
+                continue;
+            }
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Resolve `req_path` (absolute or relative to `root`) and verify it is
+/// `root` itself or a descendant of it, rejecting `..` escapes, absolute
+/// paths outside `root`, and symlinks that resolve outside `root`.
+///
+/// `req_path`'s parent directory must exist (the file itself need not, to
+/// allow writing a new file), and `root` must exist.
+pub fn resolve_within_root(root: &Path, req_path: &str) -> Result<PathBuf, String> {
+    let candidate = PathBuf::from(req_path);
+    let candidate = if candidate.is_absolute() {
+        candidate
+    } else {
+        root.join(candidate)
+    };
+
+    let canon_root = root
+        .canonicalize()
+        .map_err(|e| format!("invalid root {}: {}", root.display(), e))?;
+
+    let canon = if candidate.exists() {
+        candidate
+            .canonicalize()
+            .map_err(|e| format!("cannot resolve {}: {}", candidate.display(), e))?
+    } else {
+        let parent = candidate
+            .parent()
+            .ok_or_else(|| format!("path has no parent: {}", candidate.display()))?;
+        let file_name = candidate
+            .file_name()
+            .ok_or_else(|| format!("path has no file name: {}", candidate.display()))?;
+        let canon_parent = parent
+            .canonicalize()
+            .map_err(|e| format!("cannot resolve {}: {}", parent.display(), e))?;
+        canon_parent.join(file_name)
+    };
+
+    if canon == canon_root || canon.starts_with(&canon_root) {
+        Ok(canon)
+    } else {
+        Err(format!(
+            "path {} escapes root {}",
+            canon.display(),
+            canon_root.display()
+        ))
+    }
 }
 
 #[tauri::command]
-pub fn read_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| e.to_string())
+pub fn read_file(path: String, state: State<'_, AppState>) -> Result<String, String> {
+    let resolved = match &state.root_dir {
+        Some(root) => resolve_within_root(root, &path)?,
+        None => PathBuf::from(&path),
+    };
+    fs::read_to_string(&resolved).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn write_file(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, content).map_err(|e| e.to_string())
+pub fn write_file(path: String, content: String, state: State<'_, AppState>) -> Result<(), String> {
+    let resolved = match &state.root_dir {
+        Some(root) => resolve_within_root(root, &path)?,
+        None => PathBuf::from(&path),
+    };
+    fs::write(&resolved, content).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub fn set_current_file(path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let resolved = match &state.root_dir {
+        Some(root) => resolve_within_root(root, &path)?,
+        None => PathBuf::from(&path),
+    };
     let mut current = state.current_file.lock().map_err(|e| e.to_string())?;
-    *current = Some(PathBuf::from(path));
+    *current = Some(resolved);
     Ok(())
 }
 
@@ -34,6 +134,49 @@ pub fn get_current_file(state: State<'_, AppState>) -> Option<String> {
     current.as_ref().map(|p| p.to_string_lossy().to_string())
 }
 
+#[tauri::command]
+pub fn get_file_list(state: State<'_, AppState>) -> Vec<String> {
+    state
+        .file_list
+        .lock()
+        .map(|list| list.iter().map(|p| p.to_string_lossy().to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn next_file(state: State<'_, AppState>) -> Option<String> {
+    let list = state.file_list.lock().ok()?.clone();
+    if list.is_empty() {
+        return None;
+    }
+    let mut index = state.file_index.lock().ok()?;
+    if *index + 1 < list.len() {
+        *index += 1;
+    }
+    let path = list[*index].clone();
+    if let Ok(mut current) = state.current_file.lock() {
+        *current = Some(path.clone());
+    }
+    Some(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn prev_file(state: State<'_, AppState>) -> Option<String> {
+    let list = state.file_list.lock().ok()?.clone();
+    if list.is_empty() {
+        return None;
+    }
+    let mut index = state.file_index.lock().ok()?;
+    if *index > 0 {
+        *index -= 1;
+    }
+    let path = list[*index].clone();
+    if let Ok(mut current) = state.current_file.lock() {
+        *current = Some(path.clone());
+    }
+    Some(path.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 pub fn reveal_in_finder(path: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
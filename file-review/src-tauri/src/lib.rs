@@ -1,9 +1,26 @@
+#[cfg(feature = "web")]
+pub mod auth;
 pub mod comments;
 pub mod config;
-mod file_ops;
+pub mod file_ops;
+#[cfg(feature = "web")]
+pub mod metrics;
+pub mod protocol;
+#[cfg(feature = "web")]
+pub mod pty;
+#[cfg(feature = "web")]
+pub mod tunnel;
+#[cfg(feature = "web")]
+pub mod watcher;
+#[cfg(feature = "web")]
+pub mod web_server;
 
-use comments::{format_comments_json, format_comments_readable, parse_comments_for_output};
+use comments::{
+    collect_file_comments, format_comments_json, format_comments_json_multi, format_comments_readable,
+    format_comments_readable_multi, parse_comments_for_output,
+};
 use file_ops::AppState;
+use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::{
     menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder},
@@ -11,10 +28,22 @@ use tauri::{
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run(file_path: Option<String>, silent: bool, json_output: bool) {
+pub fn run(
+    file_path: Option<String>,
+    file_list: Vec<PathBuf>,
+    silent: bool,
+    json_output: bool,
+    stdin_mode: bool,
+    original_content: Option<String>,
+    root_dir: Option<PathBuf>,
+) {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .register_asynchronous_uri_scheme_protocol(protocol::SCHEME, |app, request, responder| {
+            let root_dir = app.state::<AppState>().root_dir.clone();
+            protocol::handle(request, responder, root_dir);
+        })
         .setup(move |app| {
             // Store file path for frontend to retrieve
             if let Some(ref path) = file_path {
@@ -34,9 +63,22 @@ pub fn run(file_path: Option<String>, silent: bool, json_output: bool) {
                 .accelerator("CmdOrCtrl+Q")
                 .build(app)?;
 
+            let next_file_item = MenuItemBuilder::new("Next File")
+                .id("next-file")
+                .accelerator("CmdOrCtrl+Right")
+                .build(app)?;
+
+            let prev_file_item = MenuItemBuilder::new("Previous File")
+                .id("prev-file")
+                .accelerator("CmdOrCtrl+Left")
+                .build(app)?;
+
             let file_menu = SubmenuBuilder::new(app, "File")
                 .item(&save_item)
                 .separator()
+                .item(&prev_file_item)
+                .item(&next_file_item)
+                .separator()
                 .item(&quit_item)
                 .build()?;
 
@@ -58,6 +100,16 @@ pub fn run(file_path: Option<String>, silent: bool, json_output: bool) {
                             let _ = window.close();
                         }
                     }
+                    "next-file" => {
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.emit("menu:next-file", ());
+                        }
+                    }
+                    "prev-file" => {
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.emit("menu:prev-file", ());
+                        }
+                    }
                     _ => {}
                 }
             });
@@ -86,14 +138,31 @@ pub fn run(file_path: Option<String>, silent: bool, json_output: bool) {
                             // Output comments if not silent
                             let state: tauri::State<'_, AppState> = app_handle.state();
                             if !state.silent {
-                                if let Some(file_path) = state.current_file.lock().ok().and_then(|f| f.clone()) {
+                                // A multi-file/directory session reports every file's
+                                // comments at once, keyed by path, rather than just
+                                // whichever file was open last.
+                                let session_files =
+                                    state.file_list.lock().map(|l| l.clone()).unwrap_or_default();
+                                if session_files.len() > 1 {
+                                    let files = collect_file_comments(&session_files);
+                                    if state.json_output {
+                                        println!("{}", format_comments_json_multi(&files));
+                                    } else {
+                                        println!("{}", format_comments_readable_multi(&files));
+                                    }
+                                } else if let Some(file_path) =
+                                    state.current_file.lock().ok().and_then(|f| f.clone())
+                                {
                                     if let Ok(content) = std::fs::read_to_string(&file_path) {
-                                        let comments = parse_comments_for_output(&content);
+                                        let comments = parse_comments_for_output(
+                                            &content,
+                                            file_path.to_str(),
+                                        );
                                         if !comments.is_empty() {
                                             if state.json_output {
-                                                println!("{}", format_comments_json(&comments));
+                                                println!("{}", format_comments_json(&content, &comments));
                                             } else {
-                                                println!("{}", format_comments_readable(&comments));
+                                                println!("{}", format_comments_readable(&content, &comments));
                                             }
                                         }
                                     }
@@ -108,16 +177,26 @@ pub fn run(file_path: Option<String>, silent: bool, json_output: bool) {
         })
         .manage(AppState {
             current_file: Mutex::new(None),
+            file_list: Mutex::new(file_list),
+            file_index: Mutex::new(0),
             silent,
             json_output,
+            stdin_mode,
+            original_content: Mutex::new(original_content),
+            root_dir,
         })
         .invoke_handler(tauri::generate_handler![
             file_ops::read_file,
             file_ops::write_file,
             file_ops::set_current_file,
             file_ops::get_current_file,
+            file_ops::get_file_list,
+            file_ops::next_file,
+            file_ops::prev_file,
             file_ops::reveal_in_finder,
             comments::parse_comments,
+            comments::parse_comments_tree,
+            comments::export_html,
             comments::insert_wrapped_comment,
             comments::insert_nextline_comment,
             comments::remove_comment,
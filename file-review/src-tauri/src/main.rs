@@ -10,6 +10,24 @@ use std::path::PathBuf;
 #[cfg(feature = "web")]
 use std::sync::{Arc, Mutex};
 
+/// Initialize the `tracing` subscriber that all startup/tunnel/web
+/// diagnostics are logged through, writing to stderr so stdout stays
+/// reserved for the parsed-comment payload. `--verbose` raises the level to
+/// `DEBUG`, `--quiet` lowers it to `WARN`; by default it's `INFO`.
+fn init_logging(verbose: bool, quiet: bool) {
+    let level = if verbose {
+        tracing::Level::DEBUG
+    } else if quiet {
+        tracing::Level::WARN
+    } else {
+        tracing::Level::INFO
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
@@ -28,6 +46,12 @@ fn main() {
     let json_output = args.iter().any(|a| a == "--json" || a == "-j");
     let web_mode = args.iter().any(|a| a == "--web" || a == "-w");
     let tunnel_enabled = args.iter().any(|a| a == "--tunnel" || a == "-t");
+    // A tunnel exposes the server beyond this machine, so it always implies
+    // auth; --auth lets a purely local --web session opt into it too.
+    let auth_enabled = tunnel_enabled || args.iter().any(|a| a == "--auth");
+    let verbose = args.iter().any(|a| a == "--verbose" || a == "-V");
+    let quiet = args.iter().any(|a| a == "--quiet" || a == "-q");
+    init_logging(verbose, quiet);
 
     // Parse --port argument
     let port: u16 = args
@@ -36,23 +60,47 @@ fn main() {
         .and_then(|w| w[1].parse().ok())
         .unwrap_or(3456);
 
-    // Extract file path (first non-flag argument after program name)
-    let file_arg = args
+    // Parse --root argument: confines read/write/set-current-file to this directory
+    let root_dir: Option<PathBuf> = args
+        .windows(2)
+        .find(|w| w[0] == "--root")
+        .map(|w| PathBuf::from(&w[1]));
+
+    // Parse --slow-request-timeout argument: seconds before a stuck /api/*
+    // request is aborted with 408 (see `enforce_slow_request_timeout`).
+    let slow_request_timeout: u64 = args
+        .windows(2)
+        .find(|w| w[0] == "--slow-request-timeout")
+        .and_then(|w| w[1].parse().ok())
+        .unwrap_or(30);
+
+    // Extract every non-flag positional argument after the program name:
+    // one or more file paths, or a single directory to review all files under.
+    // "-" (stdin) counts as positional; the values of --port, --root and
+    // --slow-request-timeout do not.
+    const VALUE_FLAGS: [&str; 3] = ["--port", "--root", "--slow-request-timeout"];
+    let file_args: Vec<String> = args
         .iter()
+        .enumerate()
         .skip(1)
-        .find(|a| !a.starts_with('-') && *a != &port.to_string())
-        .cloned();
+        .filter(|(i, a)| {
+            (!a.starts_with('-') || a.as_str() == "-")
+                && !VALUE_FLAGS.contains(&args[i - 1].as_str())
+        })
+        .map(|(_, a)| a.clone())
+        .collect();
 
     // Determine if stdin mode
-    let (file_path, stdin_mode, original_content) = match file_arg.as_deref() {
+    let (file_path, stdin_mode, original_content, file_list) = match file_args.first().map(|s| s.as_str()) {
         Some("-") => {
             // Explicit stdin mode with "-" argument
             match read_stdin_to_temp() {
                 Ok((path, content)) => {
-                    (Some(path.to_string_lossy().to_string()), true, Some(content))
+                    let path_str = path.to_string_lossy().to_string();
+                    (Some(path_str), true, Some(content), vec![path])
                 }
                 Err(e) => {
-                    eprintln!("Error reading stdin: {}", e);
+                    tracing::error!("Error reading stdin: {}", e);
                     std::process::exit(1);
                 }
             }
@@ -61,43 +109,85 @@ fn main() {
             // Auto-detect piped stdin (no file arg and stdin is not a terminal)
             match read_stdin_to_temp() {
                 Ok((path, content)) => {
-                    (Some(path.to_string_lossy().to_string()), true, Some(content))
+                    let path_str = path.to_string_lossy().to_string();
+                    (Some(path_str), true, Some(content), vec![path])
                 }
                 Err(e) => {
-                    eprintln!("Error reading stdin: {}", e);
+                    tracing::error!("Error reading stdin: {}", e);
                     std::process::exit(1);
                 }
             }
         }
-        _ => (file_arg, false, None),
+        _ => {
+            let files = collect_review_files(&file_args);
+            let file_path = files.first().map(|p| p.to_string_lossy().to_string());
+            (file_path, false, None, files)
+        }
     };
 
     // Web server mode
     #[cfg(feature = "web")]
     if web_mode {
-        run_web_mode(file_path, silent, json_output, stdin_mode, original_content, port, tunnel_enabled);
+        run_web_mode(
+            file_path,
+            file_list,
+            silent,
+            json_output,
+            stdin_mode,
+            original_content,
+            port,
+            tunnel_enabled,
+            auth_enabled,
+            root_dir,
+            slow_request_timeout,
+        );
         return;
     }
 
     #[cfg(not(feature = "web"))]
     if web_mode {
-        eprintln!("Error: Web mode requires the 'web' feature. Rebuild with: cargo build --features web");
+        tracing::error!("Web mode requires the 'web' feature. Rebuild with: cargo build --features web");
         std::process::exit(1);
     }
 
     // Tauri native mode
-    file_review_lib::run(file_path, silent, json_output, stdin_mode, original_content)
+    file_review_lib::run(
+        file_path,
+        file_list,
+        silent,
+        json_output,
+        stdin_mode,
+        original_content,
+        root_dir,
+    )
+}
+
+/// Resolve the positional file arguments into the session's ordered review
+/// queue: a single directory argument is walked for every reviewable file
+/// under it, otherwise each argument is taken as a file path directly.
+fn collect_review_files(file_args: &[String]) -> Vec<PathBuf> {
+    if let [only] = file_args {
+        let path = PathBuf::from(only);
+        if path.is_dir() {
+            return file_review_lib::file_ops::walk_reviewable_files(&path);
+        }
+    }
+    file_args.iter().map(PathBuf::from).collect()
 }
 
 #[cfg(feature = "web")]
 fn run_web_mode(
     file_path: Option<String>,
+    file_list: Vec<PathBuf>,
     silent: bool,
     json_output: bool,
     stdin_mode: bool,
     original_content: Option<String>,
     port: u16,
     tunnel_enabled: bool,
+    auth_enabled: bool,
+    root_dir: Option<PathBuf>,
+    slow_request_timeout: u64,
 ) {
     use file_review_lib::file_ops::AppState;
     use file_review_lib::tunnel::TunnelManager;
@@ -109,39 +199,55 @@ fn run_web_mode(
         // Create app state
         let app_state = Arc::new(AppState {
             current_file: Mutex::new(file_path.as_ref().map(PathBuf::from)),
+            file_list: Mutex::new(file_list),
+            file_index: Mutex::new(0),
             silent,
             json_output,
             stdin_mode,
             original_content: Mutex::new(original_content),
+            root_dir,
         });
 
         // Start web server
-        let shutdown_rx = match web_server::start_server(port, app_state).await {
-            Ok(rx) => rx,
-            Err(e) => {
-                eprintln!("Failed to start web server: {}", e);
-                std::process::exit(1);
-            }
-        };
+        let (shutdown_rx, auth_token, cors_origins) =
+            match web_server::start_server(port, app_state, auth_enabled, slow_request_timeout).await {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::error!("Failed to start web server: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+        // Token fragment appended to every URL handed to a browser, so the
+        // frontend can read it and attach it to API requests; empty when
+        // auth is disabled (plain local --web session).
+        let token_fragment = auth_token
+            .as_deref()
+            .map(|t| format!("#token={}", t))
+            .unwrap_or_default();
 
         // Handle tunnel if enabled
         let _tunnel: Option<TunnelManager> = if tunnel_enabled {
-            println!("Starting localtunnel...");
-            match TunnelManager::start(port) {
+            tracing::info!("Starting localtunnel...");
+            match TunnelManager::start(port, None) {
                 Ok(tunnel) => {
                     // Wait for the tunnel URL (up to 10 seconds)
                     if let Some(url) = tunnel.wait_for_url(10).await {
-                        println!("Tunnel URL: {}", url);
-                        println!("Share this URL for remote access.");
+                        tracing::info!("Tunnel URL: {}{}", url, token_fragment);
+                        tracing::info!("Share this URL for remote access.");
+                        file_review_lib::metrics::TUNNEL_UP.set(1);
+                        if let Ok(mut origins) = cors_origins.write() {
+                            origins.push(url.clone());
+                        }
                     } else {
-                        eprintln!("Warning: Could not get tunnel URL. Tunnel may not be working.");
-                        eprintln!("Make sure Node.js and npx are installed.");
+                        tracing::warn!("Could not get tunnel URL. Tunnel may not be working.");
+                        tracing::warn!("Make sure localtunnel.me is reachable from this machine.");
                     }
                     Some(tunnel)
                 }
                 Err(e) => {
-                    eprintln!("Failed to start tunnel: {}", e);
-                    eprintln!("Continuing without tunnel. Use local URL.");
+                    tracing::error!("Failed to start tunnel: {}", e);
+                    tracing::warn!("Continuing without tunnel. Use local URL.");
                     None
                 }
             }
@@ -149,26 +255,28 @@ fn run_web_mode(
             None
         };
 
-        // Open browser (local URL)
-        let url = format!("http://127.0.0.1:{}", port);
+        // Open browser (local URL, with the token embedded so the frontend
+        // can read it from the fragment and attach it to API requests)
+        let url = format!("http://127.0.0.1:{}{}", port, token_fragment);
         if let Err(e) = open::that(&url) {
-            eprintln!("Failed to open browser: {}", e);
-            eprintln!("Please manually open: {}", url);
+            tracing::warn!("Failed to open browser: {}", e);
+            tracing::warn!("Please manually open: {}", url);
         }
 
-        // Wait for shutdown signal or Ctrl+C
+        // Wait for the server to drain and shut down, or Ctrl+C
         tokio::select! {
             _ = shutdown_rx => {
-                println!("Shutdown signal received, exiting...");
+                tracing::info!("Server shut down gracefully, exiting...");
             }
             _ = tokio::signal::ctrl_c() => {
-                println!("\nCtrl+C received, exiting...");
+                tracing::info!("Ctrl+C received, exiting...");
             }
         }
 
         // Cleanup tunnel if it was started
         if let Some(tunnel) = _tunnel {
             tunnel.stop().await;
+            file_review_lib::metrics::TUNNEL_UP.set(0);
         }
     });
 }
@@ -188,8 +296,8 @@ fn read_stdin_to_temp() -> io::Result<(PathBuf, String)> {
 
     // Warn about large content (but still proceed)
     if content.len() > 10_000_000 {
-        eprintln!(
-            "Warning: Large content ({} bytes) may affect performance",
+        tracing::warn!(
+            "Large content ({} bytes) may affect performance",
             content.len()
         );
     }
@@ -222,11 +330,16 @@ fn print_help() {
     println!("    -h, --help       Show this help message");
     println!("    -v, --version    Show version");
     println!("    -s, --silent     Suppress output on close");
-    println!("    -j, --json       Output as JSON on close\n");
+    println!("    -j, --json       Output as JSON on close");
+    println!("    -V, --verbose    Log debug-level diagnostics to stderr");
+    println!("    -q, --quiet      Only log warnings/errors to stderr\n");
     println!("WEB MODE:");
     println!("    -w, --web        Start in web server mode (opens browser)");
     println!("    -t, --tunnel     Enable localtunnel for remote access (requires --web)");
-    println!("    --port PORT      HTTP server port (default: 3456)\n");
+    println!("    --auth           Require a bearer token for API requests (implied by --tunnel)");
+    println!("    --port PORT      HTTP server port (default: 3456)");
+    println!("    --root DIR       Confine read/write/set-current-file to this directory");
+    println!("    --slow-request-timeout SECS   Abort a stuck /api/* request after this many seconds (default: 30)\n");
     println!("OUTPUT:");
     println!("    By default, review comments are printed to stdout when");
     println!("    the application closes. Use --silent to suppress this,");
@@ -0,0 +1,52 @@
+//! Prometheus metrics for the web server: per-endpoint request counts and
+//! latencies, active PTY/WebSocket sessions, and tunnel up/down state.
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, Encoder, HistogramVec,
+    IntCounterVec, IntGauge, TextEncoder,
+};
+
+pub static HTTP_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "file_review_http_requests_total",
+        "Total HTTP requests, by path, method, and status code",
+        &["path", "method", "status"]
+    )
+    .unwrap()
+});
+
+pub static HTTP_REQUEST_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "file_review_http_request_duration_seconds",
+        "HTTP request latency, by path and method",
+        &["path", "method"]
+    )
+    .unwrap()
+});
+
+pub static ACTIVE_PTY_SESSIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "file_review_active_pty_sessions",
+        "Number of currently open PTY/WebSocket terminal sessions"
+    )
+    .unwrap()
+});
+
+pub static TUNNEL_UP: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "file_review_tunnel_up",
+        "1 if the tunnel is currently established, 0 otherwise"
+    )
+    .unwrap()
+});
+
+/// Render all registered metrics in the Prometheus text exposition format.
+pub fn encode() -> String {
+    let metric_families = prometheus::gather();
+    let mut buf = Vec::new();
+    TextEncoder::new()
+        .encode(&metric_families, &mut buf)
+        .unwrap_or_default();
+    String::from_utf8(buf).unwrap_or_default()
+}
@@ -0,0 +1,141 @@
+//! Custom `freview://` URI scheme for serving local file content to the
+//! webview asynchronously and with byte-range support, instead of blocking
+//! the main thread on `fs::read_to_string` via the `read_file` command.
+//!
+//! Requests look like `freview://local/<percent-encoded-absolute-path>`.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::UriSchemeResponder;
+
+use crate::file_ops::resolve_within_root;
+
+/// Scheme name passed to `register_asynchronous_uri_scheme_protocol` in `run()`.
+pub const SCHEME: &str = "freview";
+
+/// Handle one `freview://` request off the main thread and hand the
+/// response back through `responder` once the read completes. `root_dir`
+/// mirrors `read_file`/`write_file`'s confinement: `Some(dir)` rejects any
+/// requested path outside it, `None` leaves paths unrestricted (native CLI
+/// use, where the reviewed file can be anywhere).
+pub fn handle(request: Request<Vec<u8>>, responder: UriSchemeResponder, root_dir: Option<PathBuf>) {
+    std::thread::spawn(move || {
+        responder.respond(build_response(&request, root_dir.as_deref()));
+    });
+}
+
+fn build_response(request: &Request<Vec<u8>>, root_dir: Option<&Path>) -> Response<Vec<u8>> {
+    let requested = match decode_path(request.uri().path()) {
+        Some(path) => path,
+        None => return error_response(StatusCode::BAD_REQUEST, "invalid freview:// path"),
+    };
+
+    let path = match root_dir {
+        Some(root) => match resolve_within_root(root, &requested.to_string_lossy()) {
+            Ok(resolved) => resolved,
+            Err(e) => return error_response(StatusCode::FORBIDDEN, &e),
+        },
+        None => requested,
+    };
+
+    let mut file = match File::open(&path) {
+        Ok(f) => f,
+        Err(e) => return error_response(StatusCode::NOT_FOUND, &e.to_string()),
+    };
+
+    let len = match file.metadata() {
+        Ok(meta) => meta.len(),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    };
+
+    let mime = mime_for_path(&path);
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_range);
+
+    match range {
+        Some((start, end)) if start < len => {
+            let end = end.unwrap_or(len - 1).min(len - 1);
+            let chunk_len = end - start + 1;
+            let mut buf = vec![0u8; chunk_len as usize];
+            if file.seek(SeekFrom::Start(start)).is_err() || file.read_exact(&mut buf).is_err() {
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to read range");
+            }
+            Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_TYPE, mime)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                .header(header::ACCEPT_RANGES, "bytes")
+                .header(header::CONTENT_LENGTH, chunk_len.to_string())
+                .body(buf)
+                .unwrap_or_else(|_| error_response(StatusCode::INTERNAL_SERVER_ERROR, "response build failed"))
+        }
+        _ => {
+            let mut buf = Vec::with_capacity(len as usize);
+            if file.read_to_end(&mut buf).is_err() {
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to read file");
+            }
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, mime)
+                .header(header::ACCEPT_RANGES, "bytes")
+                .header(header::CONTENT_LENGTH, buf.len().to_string())
+                .body(buf)
+                .unwrap_or_else(|_| error_response(StatusCode::INTERNAL_SERVER_ERROR, "response build failed"))
+        }
+    }
+}
+
+fn decode_path(uri_path: &str) -> Option<PathBuf> {
+    let encoded = uri_path.trim_start_matches('/');
+    percent_decode(encoded).map(PathBuf::from)
+}
+
+/// Minimal `%XX` percent-decoding, to avoid pulling in a dedicated crate
+/// for the handful of escapes a file path can contain.
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Parse a single-range `Range: bytes=start-end` header. Multi-range
+/// requests (comma-separated) aren't supported and fall back to a full read.
+fn parse_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let spec = value.strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let start: u64 = start.parse().ok()?;
+    let end = if end.is_empty() { None } else { Some(end.parse().ok()?) };
+    Some((start, end))
+}
+
+fn mime_for_path(path: &Path) -> String {
+    mime_guess::from_path(path).first_or_octet_stream().to_string()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_else(|_| Response::new(Vec::new()))
+}
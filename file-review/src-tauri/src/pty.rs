@@ -0,0 +1,250 @@
+//! WebSocket-backed PTY terminal sessions for the web server.
+//!
+//! Mirrors the Tauri `create_pty_session`/`write_to_pty`/`resize_pty`/
+//! `close_pty_session` commands (see `hive`'s lib.rs), but streams PTY
+//! output over a WebSocket frame instead of a Tauri `emit`, so the same
+//! `claude` terminal works whether the app is run natively or tunneled.
+
+use axum::extract::ws::{Message, WebSocket};
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Mutex;
+
+/// A single live PTY, keyed by session id in [`PtyRegistry`].
+pub struct PtySession {
+    writer: Box<dyn Write + Send>,
+    master: Box<dyn MasterPty + Send>,
+}
+
+/// Registry of active PTY sessions, mirroring the Tauri `AppState` in hive.
+#[derive(Default)]
+pub struct PtyRegistry {
+    sessions: Mutex<HashMap<String, PtySession>>,
+}
+
+impl PtyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&self, session_id: String, session: PtySession) {
+        self.sessions.lock().unwrap().insert(session_id, session);
+        crate::metrics::ACTIVE_PTY_SESSIONS.inc();
+    }
+
+    fn remove(&self, session_id: &str) {
+        if self.sessions.lock().unwrap().remove(session_id).is_some() {
+            crate::metrics::ACTIVE_PTY_SESSIONS.dec();
+        }
+    }
+
+    fn write(&self, session_id: &str, data: &[u8]) -> std::io::Result<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no such pty session"))?;
+        session.writer.write_all(data)?;
+        session.writer.flush()
+    }
+
+    fn resize(&self, session_id: &str, rows: u16, cols: u16) -> std::io::Result<()> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no such pty session"))?;
+        session.master.resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+    }
+}
+
+/// Query parameters accepted on the `/api/pty/{session_id}` upgrade,
+/// reusing the same fields as the Tauri `CreatePtyRequest`.
+#[derive(Debug, Deserialize)]
+pub struct CreatePtyParams {
+    pub cwd: Option<String>,
+    #[serde(default = "default_rows")]
+    pub rows: u16,
+    #[serde(default = "default_cols")]
+    pub cols: u16,
+    pub resume_session: Option<String>,
+}
+
+fn default_rows() -> u16 {
+    24
+}
+
+fn default_cols() -> u16 {
+    80
+}
+
+/// Control messages a client may send over the PTY WebSocket as JSON text
+/// frames; anything else (binary frames, or text that isn't valid JSON for
+/// this enum) is treated as raw keystroke input.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ControlMessage {
+    Resize { rows: u16, cols: u16 },
+}
+
+/// Drive one PTY-backed WebSocket connection until the socket or the child
+/// process closes. Opens the PTY, registers it in `registry`, spawns a
+/// background task to forward PTY output to the socket, and relays inbound
+/// frames to the PTY writer (or the resize control) until the socket
+/// closes, the PTY output ends, or the child exits. When the child's exit is
+/// what ends the loop, a close frame carrying the exit code is sent first.
+/// The session is removed from `registry` in every case.
+pub async fn handle_pty_socket(
+    mut socket: WebSocket,
+    registry: std::sync::Arc<PtyRegistry>,
+    session_id: String,
+    params: CreatePtyParams,
+) {
+    let pty_system = native_pty_system();
+    let pair = match pty_system.openpty(PtySize {
+        rows: params.rows,
+        cols: params.cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    }) {
+        Ok(pair) => pair,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(format!("failed to open pty: {}", e).into()))
+                .await;
+            return;
+        }
+    };
+
+    let mut cmd = CommandBuilder::new("claude");
+    if let Some(resume_id) = &params.resume_session {
+        cmd.arg("--resume");
+        cmd.arg(resume_id);
+    }
+    for (key, value) in std::env::vars() {
+        cmd.env(key, value);
+    }
+    cmd.env("TERM", "xterm-256color");
+    cmd.env("COLORTERM", "truecolor");
+    cmd.env("TERM_PROGRAM", "file-review");
+    cmd.env("LANG", "en_US.UTF-8");
+    cmd.env("LC_ALL", "en_US.UTF-8");
+    if let Some(cwd) = &params.cwd {
+        cmd.cwd(cwd);
+    }
+
+    let mut child = match pair.slave.spawn_command(cmd) {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(format!("failed to spawn claude: {}", e).into()))
+                .await;
+            return;
+        }
+    };
+
+    let writer = match pair.master.take_writer() {
+        Ok(w) => w,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(format!("failed to take pty writer: {}", e).into()))
+                .await;
+            return;
+        }
+    };
+    let mut reader = match pair.master.try_clone_reader() {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(format!("failed to clone pty reader: {}", e).into()))
+                .await;
+            return;
+        }
+    };
+
+    registry.insert(
+        session_id.clone(),
+        PtySession {
+            writer,
+            master: pair.master,
+        },
+    );
+
+    // Forward PTY output to the socket over a channel, since the reader is
+    // blocking and the socket must stay on the async task.
+    let (output_tx, mut output_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 16384];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if output_tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    let (exit_tx, exit_rx) = tokio::sync::oneshot::channel::<Option<i32>>();
+    std::thread::spawn(move || {
+        let status = child.wait();
+        let code = status.ok().map(|s| s.exit_code() as i32);
+        let _ = exit_tx.send(code);
+    });
+    tokio::pin!(exit_rx);
+
+    loop {
+        tokio::select! {
+            data = output_rx.recv() => {
+                match data {
+                    Some(bytes) => {
+                        if socket.send(Message::Binary(bytes.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+            code = &mut exit_rx => {
+                let code = code.unwrap_or(None);
+                let _ = socket
+                    .send(Message::Close(Some(axum::extract::ws::CloseFrame {
+                        code: 1000,
+                        reason: format!("exit:{}", code.unwrap_or(-1)).into(),
+                    })))
+                    .await;
+                break;
+            }
+            msg = socket.recv() => {
+                match msg {
+                    Some(Ok(Message::Binary(data))) => {
+                        let _ = registry.write(&session_id, &data);
+                    }
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<ControlMessage>(&text) {
+                            Ok(ControlMessage::Resize { rows, cols }) => {
+                                let _ = registry.resize(&session_id, rows, cols);
+                            }
+                            Err(_) => {
+                                let _ = registry.write(&session_id, text.as_bytes());
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) => break,
+                }
+            }
+        }
+    }
+
+    registry.remove(&session_id);
+}
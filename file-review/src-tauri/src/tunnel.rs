@@ -1,116 +1,384 @@
 //! Tunnel management for exposing local server via localtunnel
 //!
-//! Uses a Node.js subprocess to run localtunnel for reliable tunneling.
+//! Two implementations are available behind the [`TunnelBackend`] trait:
+//! [`LocaltunnelClient`] speaks the public `localtunnel.me` wire protocol
+//! natively (no Node.js/npx dependency), and [`RelayTunnel`] dials out to a
+//! self-hosted relay host over a persistent connection (no inbound port),
+//! modeled on the PTTH relay pattern. [`TunnelManager`] picks one at
+//! construction time and exposes the same
+//! `start`/`get_url`/`wait_for_url`/`stop` surface either way.
 
-use std::io::{BufRead, BufReader};
-use std::process::{Child, Command, Stdio};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 
-/// Manages a localtunnel subprocess
+/// Surface every tunnel implementation exposes.
+#[async_trait]
+pub trait TunnelBackend: Send + Sync {
+    /// The public tunnel URL, once known.
+    async fn get_url(&self) -> Option<String>;
+    /// Tear down the tunnel.
+    async fn stop(&self);
+}
+
+/// Poll `backend.get_url()` until it resolves or `timeout_secs` elapses.
+async fn wait_for_url(backend: &dyn TunnelBackend, timeout_secs: u64) -> Option<String> {
+    let start = std::time::Instant::now();
+    let timeout = std::time::Duration::from_secs(timeout_secs);
+
+    while start.elapsed() < timeout {
+        if let Some(url) = backend.get_url().await {
+            return Some(url);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    None
+}
+
+/// Which [`TunnelBackend`] [`TunnelManager::start_with`] should construct.
+pub enum TunnelKind {
+    /// Native `localtunnel.me` client (default), no Node.js/npx dependency.
+    Localtunnel,
+    /// Native relay client, no external process or inbound port required.
+    Relay { relay_host: String },
+}
+
+/// Owns the active tunnel backend and forwards the stable API to it.
 pub struct TunnelManager {
-    process: Arc<Mutex<Option<Child>>>,
-    public_url: Arc<Mutex<Option<String>>>,
+    backend: Box<dyn TunnelBackend>,
 }
 
 impl TunnelManager {
-    /// Start a new tunnel on the specified port with optional subdomain
-    ///
-    /// This spawns `npx @desplega.ai/localtunnel --port PORT [--subdomain SUBDOMAIN]` as a subprocess
-    /// and parses the stdout to get the public URL.
+    /// Start a tunnel using the default (native localtunnel.me) backend.
     pub fn start(port: u16, subdomain: Option<&str>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        // Build args - need owned strings for lifetime
-        let port_str = port.to_string();
-        let mut args = vec!["@desplega.ai/localtunnel", "--port", &port_str];
-        let subdomain_owned: String;
-        if let Some(sub) = subdomain {
-            subdomain_owned = sub.to_string();
-            args.push("--subdomain");
-            args.push(&subdomain_owned);
+        Self::start_with(port, subdomain, TunnelKind::Localtunnel)
+    }
+
+    /// Start a tunnel using the given backend.
+    pub fn start_with(
+        port: u16,
+        subdomain: Option<&str>,
+        kind: TunnelKind,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let backend: Box<dyn TunnelBackend> = match kind {
+            TunnelKind::Localtunnel => Box::new(LocaltunnelClient::start(port, subdomain)),
+            TunnelKind::Relay { relay_host } => Box::new(RelayTunnel::start(port, subdomain, relay_host)),
+        };
+        Ok(Self { backend })
+    }
+
+    /// Get the public tunnel URL, if available.
+    pub async fn get_url(&self) -> Option<String> {
+        self.backend.get_url().await
+    }
+
+    /// Wait for the tunnel URL to become available (with timeout).
+    pub async fn wait_for_url(&self, timeout_secs: u64) -> Option<String> {
+        wait_for_url(self.backend.as_ref(), timeout_secs).await
+    }
+
+    /// Stop the tunnel.
+    pub async fn stop(&self) {
+        self.backend.stop().await;
+    }
+}
+
+/// Response body from `GET https://localtunnel.me/?new`.
+#[derive(Debug, Deserialize)]
+struct LocaltunnelRegisterResponse {
+    /// Remote port on `localtunnel.me` that data connections dial.
+    port: u16,
+    /// How many concurrent data connections the server will accept.
+    max_conn_count: u16,
+    /// The public URL assigned to this tunnel.
+    url: String,
+}
+
+/// Native client for the public `localtunnel.me` service: registers over
+/// HTTPS, then keeps up to `max_conn_count` raw TCP connections open to
+/// `localtunnel.me:<port>`, each bridged to `127.0.0.1:<local_port>` via
+/// `copy_bidirectional`, reopening a replacement as each one closes. No
+/// subprocess, no Node.js dependency.
+pub struct LocaltunnelClient {
+    public_url: Arc<Mutex<Option<String>>>,
+    stopped: Arc<AtomicBool>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl LocaltunnelClient {
+    /// Register with `localtunnel.me` and start maintaining the connection
+    /// pool. Never fails synchronously: registration and pool maintenance
+    /// happen on a background task, and `get_url` simply stays `None` until
+    /// the handshake completes (or forever, if localtunnel.me is unreachable).
+    pub fn start(local_port: u16, subdomain: Option<&str>) -> Self {
+        let public_url = Arc::new(Mutex::new(None::<String>));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let subdomain = subdomain.map(|s| s.to_string());
+
+        let handshake_url = public_url.clone();
+        let handshake_stopped = stopped.clone();
+        let handshake_task = tokio::spawn(async move {
+            match Self::register(subdomain.as_deref()).await {
+                Ok(resp) => {
+                    *handshake_url.lock().await = Some(resp.url);
+                    Self::maintain_pool(resp.port, resp.max_conn_count, local_port, handshake_stopped).await;
+                }
+                Err(e) => {
+                    tracing::error!("localtunnel registration failed: {}", e);
+                }
+            }
+        });
+
+        Self {
+            public_url,
+            stopped,
+            tasks: Mutex::new(vec![handshake_task]),
         }
+    }
 
-        // Try to spawn the localtunnel process
-        let mut child = Command::new("npx")
-            .args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start localtunnel: {}. Make sure Node.js/npm is installed.", e))?;
+    /// `GET https://localtunnel.me/?new` (or `/<subdomain>` for a requested
+    /// name) and parse the JSON `{id, port, max_conn_count, url}` response.
+    async fn register(
+        subdomain: Option<&str>,
+    ) -> Result<LocaltunnelRegisterResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let path = match subdomain {
+            Some(sub) => format!("https://localtunnel.me/{}", sub),
+            None => "https://localtunnel.me/?new".to_string(),
+        };
+        let resp = reqwest::get(&path)
+            .await?
+            .json::<LocaltunnelRegisterResponse>()
+            .await?;
+        Ok(resp)
+    }
 
-        // Read stdout to get the URL
-        let stdout = child.stdout.take()
-            .ok_or("Failed to capture stdout from localtunnel")?;
+    /// Keep up to `max_conn_count` idle data connections open to
+    /// `localtunnel.me:<remote_port>`, each ready to be handed an inbound
+    /// request and proxied to `127.0.0.1:local_port`. Each slot is its own
+    /// independent loop, so a connection that closes is replaced as soon as
+    /// it closes rather than waiting for the rest of the pool to drain too.
+    async fn maintain_pool(remote_port: u16, max_conn_count: u16, local_port: u16, stopped: Arc<AtomicBool>) {
+        let mut handles = Vec::with_capacity(max_conn_count as usize);
+        for _ in 0..max_conn_count {
+            let stopped = stopped.clone();
+            handles.push(tokio::spawn(async move {
+                while !stopped.load(Ordering::Relaxed) {
+                    Self::serve_one_connection(remote_port, local_port).await;
+                }
+            }));
+        }
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
 
+    /// Open one data connection to `localtunnel.me:<remote_port>`, wait for
+    /// it to start carrying an inbound request, then bridge it to the local
+    /// axum server until either side closes.
+    async fn serve_one_connection(remote_port: u16, local_port: u16) {
+        let mut remote_conn = match TcpStream::connect(("localtunnel.me", remote_port)).await {
+            Ok(conn) => conn,
+            Err(_) => {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                return;
+            }
+        };
+
+        if remote_conn.readable().await.is_err() {
+            return;
+        }
+
+        let mut local_conn = match TcpStream::connect(("127.0.0.1", local_port)).await {
+            Ok(conn) => conn,
+            Err(_) => return,
+        };
+
+        let _ = tokio::io::copy_bidirectional(&mut remote_conn, &mut local_conn).await;
+    }
+}
+
+#[async_trait]
+impl TunnelBackend for LocaltunnelClient {
+    async fn get_url(&self) -> Option<String> {
+        self.public_url.lock().await.clone()
+    }
+
+    async fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        let mut tasks = self.tasks.lock().await;
+        for task in tasks.drain(..) {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for LocaltunnelClient {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Ok(mut tasks) = self.tasks.try_lock() {
+            for task in tasks.drain(..) {
+                task.abort();
+            }
+        }
+    }
+}
+
+/// Native relay-client tunnel: dials `relay_host` and registers under a
+/// subdomain/key, keeping a persistent connection open; the relay reverse-
+/// proxies inbound HTTP requests back down that connection to the local
+/// axum app. No inbound port, no Node dependency, no stdout scraping.
+pub struct RelayTunnel {
+    public_url: Arc<Mutex<Option<String>>>,
+    stopped: Arc<AtomicBool>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+/// How many idle data connections the relay client keeps open at once, so
+/// an inbound request can be proxied without waiting on a fresh dial.
+const POOL_SIZE: usize = 4;
+
+impl RelayTunnel {
+    /// Register with `relay_host` and start maintaining the connection pool.
+    /// Never fails synchronously: the registration and pool-maintenance
+    /// happen on a background task, and `get_url` simply stays `None` until
+    /// the handshake completes (or forever, if the relay is unreachable).
+    pub fn start(local_port: u16, subdomain: Option<&str>, relay_host: String) -> Self {
         let public_url = Arc::new(Mutex::new(None::<String>));
-        let url_clone = public_url.clone();
-
-        // Spawn a thread to read the URL from stdout
-        std::thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    // Print tunnel output for debugging
-                    eprintln!("[tunnel] {}", line);
-
-                    // Localtunnel prints "your url is: https://xxx.lt.desplega.ai"
-                    if line.to_lowercase().contains("your url is:") {
-                        if let Some(url) = line.split_whitespace().last() {
-                            let mut guard = url_clone.blocking_lock();
-                            *guard = Some(url.to_string());
-                        }
-                    } else if line.starts_with("https://") {
-                        // Some versions just print the URL directly
-                        let mut guard = url_clone.blocking_lock();
-                        *guard = Some(line);
-                    }
+        let stopped = Arc::new(AtomicBool::new(false));
+        let subdomain = subdomain.map(|s| s.to_string());
+
+        let handshake_url = public_url.clone();
+        let handshake_stopped = stopped.clone();
+        let handshake_relay_host = relay_host.clone();
+        let handshake_task = tokio::spawn(async move {
+            match Self::register(&handshake_relay_host, subdomain.as_deref()).await {
+                Ok((url, key)) => {
+                    *handshake_url.lock().await = Some(url);
+                    Self::maintain_pool(handshake_relay_host, key, local_port, handshake_stopped).await;
+                }
+                Err(e) => {
+                    tracing::error!("relay tunnel registration failed: {}", e);
                 }
             }
         });
 
-        Ok(Self {
-            process: Arc::new(Mutex::new(Some(child))),
+        Self {
             public_url,
-        })
+            stopped,
+            tasks: Mutex::new(vec![handshake_task]),
+        }
     }
 
-    /// Get the public tunnel URL, if available
-    pub async fn get_url(&self) -> Option<String> {
-        let guard = self.public_url.lock().await;
-        guard.clone()
+    /// Dial the relay's control port and register for a public URL.
+    /// Handshake protocol: client sends `REGISTER [subdomain]\n`, relay
+    /// replies `OK <url> <key>\n` or `ERR <message>\n`.
+    async fn register(
+        relay_host: &str,
+        subdomain: Option<&str>,
+    ) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = TcpStream::connect(relay_host).await?;
+        let request = match subdomain {
+            Some(sub) => format!("REGISTER {}\n", sub),
+            None => "REGISTER\n".to_string(),
+        };
+        conn.write_all(request.as_bytes()).await?;
+
+        let mut reader = tokio::io::BufReader::new(conn);
+        let mut line = String::new();
+        tokio::io::AsyncBufReadExt::read_line(&mut reader, &mut line).await?;
+
+        let mut parts = line.trim().splitn(3, ' ');
+        match parts.next() {
+            Some("OK") => {
+                let url = parts.next().ok_or("relay handshake missing url")?.to_string();
+                let key = parts.next().ok_or("relay handshake missing key")?.to_string();
+                Ok((url, key))
+            }
+            _ => Err(format!("relay registration failed: {}", line.trim()).into()),
+        }
     }
 
-    /// Wait for the tunnel URL to become available (with timeout)
-    pub async fn wait_for_url(&self, timeout_secs: u64) -> Option<String> {
-        let start = std::time::Instant::now();
-        let timeout = std::time::Duration::from_secs(timeout_secs);
+    /// Keep `POOL_SIZE` idle data connections open to the relay, each ready
+    /// to be handed an inbound request and proxied to `127.0.0.1:local_port`.
+    /// Each slot is its own independent loop, so a connection that closes
+    /// is replaced as soon as it closes rather than waiting for the rest of
+    /// the pool to drain too.
+    async fn maintain_pool(relay_host: String, key: String, local_port: u16, stopped: Arc<AtomicBool>) {
+        let mut handles = Vec::with_capacity(POOL_SIZE);
+        for _ in 0..POOL_SIZE {
+            let relay_host = relay_host.clone();
+            let key = key.clone();
+            let stopped = stopped.clone();
+            handles.push(tokio::spawn(async move {
+                while !stopped.load(Ordering::Relaxed) {
+                    Self::serve_one_connection(&relay_host, &key, local_port).await;
+                }
+            }));
+        }
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
 
-        while start.elapsed() < timeout {
-            if let Some(url) = self.get_url().await {
-                return Some(url);
+    /// Open one data connection, wait for the relay to start forwarding an
+    /// inbound request over it, then bridge it to the local axum server.
+    async fn serve_one_connection(relay_host: &str, key: &str, local_port: u16) {
+        let mut relay_conn = match TcpStream::connect(relay_host).await {
+            Ok(conn) => conn,
+            Err(_) => {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                return;
             }
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        };
+
+        if relay_conn
+            .write_all(format!("DATA {}\n", key).as_bytes())
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        if relay_conn.readable().await.is_err() {
+            return;
         }
 
-        None
+        let mut local_conn = match TcpStream::connect(("127.0.0.1", local_port)).await {
+            Ok(conn) => conn,
+            Err(_) => return,
+        };
+
+        let _ = tokio::io::copy_bidirectional(&mut relay_conn, &mut local_conn).await;
     }
+}
 
-    /// Stop the tunnel subprocess
-    pub async fn stop(&self) {
-        let mut guard = self.process.lock().await;
-        if let Some(mut child) = guard.take() {
-            // Try to kill the process gracefully
-            let _ = child.kill();
-            let _ = child.wait();
+#[async_trait]
+impl TunnelBackend for RelayTunnel {
+    async fn get_url(&self) -> Option<String> {
+        self.public_url.lock().await.clone()
+    }
+
+    async fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        let mut tasks = self.tasks.lock().await;
+        for task in tasks.drain(..) {
+            task.abort();
         }
     }
 }
 
-impl Drop for TunnelManager {
+impl Drop for RelayTunnel {
     fn drop(&mut self) {
-        // Try to clean up the process on drop
-        if let Ok(mut guard) = self.process.try_lock() {
-            if let Some(mut child) = guard.take() {
-                let _ = child.kill();
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Ok(mut tasks) = self.tasks.try_lock() {
+            for task in tasks.drain(..) {
+                task.abort();
             }
         }
     }
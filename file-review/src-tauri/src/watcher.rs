@@ -0,0 +1,108 @@
+//! Filesystem watcher that turns edits to the reviewed file into
+//! Server-Sent Events so the web UI can auto-refresh without polling.
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// One change notification pushed to `/api/events` subscribers.
+#[derive(Debug, Clone)]
+pub enum FileEvent {
+    Changed { content: String },
+    Removed,
+}
+
+/// Watches a single path at a time (the current review file), rebroadcasting
+/// debounced change/removal notifications to any number of SSE subscribers.
+pub struct FileWatcher {
+    tx: broadcast::Sender<FileEvent>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        // Bounded so a slow subscriber can't unbound memory growth; a
+        // dropped event just means the client does one extra `read_file`.
+        let (tx, _rx) = broadcast::channel(16);
+        Self {
+            tx,
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<FileEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Replace the watched path, tearing down any previous watch.
+    pub fn watch(&self, path: &Path) {
+        let tx = self.tx.clone();
+        let watched_path = path.to_path_buf();
+        let mut last_sent = None::<std::time::Instant>;
+
+        let mut watcher = match notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else { return };
+            if !matches!(
+                event.kind,
+                EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+            ) {
+                return;
+            }
+
+            // Coalesce rapid successive writes (e.g. from an editor or
+            // formatter) within DEBOUNCE instead of flooding the stream.
+            let now = std::time::Instant::now();
+            if let Some(prev) = last_sent {
+                if now.duration_since(prev) < DEBOUNCE {
+                    return;
+                }
+            }
+            last_sent = Some(now);
+
+            let file_event = if matches!(event.kind, EventKind::Remove(_)) {
+                FileEvent::Removed
+            } else {
+                match std::fs::read_to_string(&watched_path) {
+                    Ok(content) => FileEvent::Changed { content },
+                    Err(_) => return,
+                }
+            };
+            let _ = tx.send(file_event);
+        }) {
+            Ok(w) => w,
+            Err(e) => {
+                eprintln!("[watcher] failed to create watcher: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = watcher.watch(path, RecursiveMode::NonRecursive) {
+            eprintln!("[watcher] failed to watch {}: {}", path.display(), e);
+            return;
+        }
+
+        *self.watcher.lock().unwrap() = Some(watcher);
+    }
+
+    /// Stop watching, if anything was being watched.
+    pub fn unwatch(&self) {
+        *self.watcher.lock().unwrap() = None;
+    }
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn rewatch(watcher: &FileWatcher, path: Option<&PathBuf>) {
+    match path {
+        Some(p) => watcher.watch(p),
+        None => watcher.unwatch(),
+    }
+}
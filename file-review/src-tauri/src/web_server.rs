@@ -3,27 +3,42 @@
 //! This module provides an HTTP server that serves the frontend and exposes
 //! REST API endpoints equivalent to the Tauri commands.
 
+use crate::auth::{generate_token, is_allowlisted, ApprovalDecision, ApprovalError, ApprovalRegistry};
 use crate::comments::{
-    format_comments_json, format_comments_readable, format_stdin_output_json,
-    format_stdin_output_readable, parse_comments_for_output, insert_nextline_comment as insert_nextline_comment_internal,
+    collect_file_comments, format_comments_json, format_comments_json_multi, format_comments_readable,
+    format_comments_readable_multi, format_stdin_output_json, format_stdin_output_readable,
+    parse_comments_for_output, insert_nextline_comment as insert_nextline_comment_internal,
     insert_wrapped_comment as insert_wrapped_comment_internal, remove_comment as remove_comment_internal,
 };
 use crate::config::{load_config as load_config_internal, save_config as save_config_internal, AppConfig};
-use crate::file_ops::AppState;
+use crate::file_ops::{resolve_within_root, AppState};
+use crate::pty::{handle_pty_socket, CreatePtyParams, PtyRegistry};
+use crate::watcher::{rewatch, FileEvent, FileWatcher};
 use axum::{
     body::Body,
-    extract::{Extension, Json, Path},
+    extract::{
+        ws::WebSocketUpgrade, Extension, Json, MatchedPath, Path, Query, Request,
+    },
     http::{header, Response, StatusCode},
-    response::IntoResponse,
+    middleware::{self, Next},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        Html, IntoResponse,
+    },
     routing::{get, post},
     Router,
 };
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
-use tokio::sync::oneshot;
-use tower_http::cors::{Any, CorsLayer};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, oneshot};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use std::sync::RwLock;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
 /// Embedded frontend assets from the dist folder
 #[derive(RustEmbed)]
@@ -33,7 +48,28 @@ struct Assets;
 /// Shared state for the web server
 pub struct WebState {
     pub app_state: Arc<AppState>,
-    pub shutdown_tx: Mutex<Option<oneshot::Sender<()>>>,
+    /// Broadcasts the graceful-shutdown signal to the serving task.
+    pub shutdown_tx: broadcast::Sender<()>,
+    /// Bearer token required on every `/api/*` request, when auth is
+    /// enabled (see [`start_server`]). `None` means the server trusts
+    /// anything that can reach it, which is only safe for a purely local
+    /// `--web` session with no tunnel.
+    pub auth_token: Option<String>,
+    /// Paths that `write_file` may touch without operator approval.
+    pub write_allowlist: Vec<PathBuf>,
+    /// Pending out-of-allowlist writes awaiting an operator decision.
+    pub approvals: ApprovalRegistry,
+    /// Active PTY terminal sessions opened over `/api/pty/{session_id}`.
+    pub pty_sessions: Arc<PtyRegistry>,
+    /// Watches `app_state.current_file` and feeds `/api/events`.
+    pub watcher: FileWatcher,
+    /// When the server started, for the `QuitResponse` served-duration report.
+    pub started_at: Instant,
+    /// Requests slower than this are aborted with `408 Request Timeout`.
+    pub slow_request_timeout: Duration,
+    /// Allowed CORS origins, composed from `AppConfig.cors_origins` plus the
+    /// live tunnel URL once one is known. Empty means "allow any origin".
+    pub cors_origins: Arc<RwLock<Vec<String>>>,
 }
 
 /// Response for the quit endpoint
@@ -42,6 +78,7 @@ pub struct QuitResponse {
     pub success: bool,
     pub output: String,
     pub comments_count: usize,
+    pub served_secs: f64,
 }
 
 /// Request body for read_file
@@ -67,6 +104,8 @@ pub struct SetCurrentFileRequest {
 #[derive(Deserialize)]
 pub struct ParseCommentsRequest {
     pub content: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
 }
 
 /// Request body for insert_wrapped_comment
@@ -76,6 +115,8 @@ pub struct InsertWrappedCommentRequest {
     pub start_pos: usize,
     pub end_pos: usize,
     pub text: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
 }
 
 /// Request body for insert_nextline_comment
@@ -85,6 +126,8 @@ pub struct InsertNextlineCommentRequest {
     pub line_start_pos: usize,
     pub line_end_pos: usize,
     pub text: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
 }
 
 /// Request body for remove_comment
@@ -92,6 +135,8 @@ pub struct InsertNextlineCommentRequest {
 pub struct RemoveCommentRequest {
     pub content: String,
     pub comment_id: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
 }
 
 /// Response for insert comment operations
@@ -101,12 +146,30 @@ pub struct InsertCommentResponse {
     pub id: String,
 }
 
+/// Request body for resolving a pending write approval
+#[derive(Deserialize)]
+pub struct ApproveRequest {
+    pub decision: String, // "approve" or "deny"
+}
+
 /// Create the router with all API endpoints
 pub fn create_router(state: Arc<WebState>) -> Router {
+    let cors_origins = state.cors_origins.clone();
     let cors = CorsLayer::new()
-        .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .allow_origin(AllowOrigin::predicate(move |origin, _parts| {
+            let origins = cors_origins.read().unwrap();
+            // No origins configured and no tunnel active: stay open, same
+            // as today. Otherwise only the composed allowlist may pass.
+            if origins.is_empty() {
+                return true;
+            }
+            origin
+                .to_str()
+                .map(|o| origins.iter().any(|allowed| allowed == o))
+                .unwrap_or(false)
+        }));
 
     Router::new()
         // Static assets
@@ -115,6 +178,9 @@ pub fn create_router(state: Arc<WebState>) -> Router {
         // API endpoints
         .route("/api/version", get(get_version))
         .route("/api/current-file", get(get_current_file))
+        .route("/api/file-list", get(get_file_list))
+        .route("/api/next-file", post(next_file))
+        .route("/api/prev-file", post(prev_file))
         .route("/api/read-file", post(read_file))
         .route("/api/write-file", post(write_file))
         .route("/api/set-current-file", post(set_current_file))
@@ -122,16 +188,148 @@ pub fn create_router(state: Arc<WebState>) -> Router {
         .route("/api/config", get(get_config))
         .route("/api/config", post(post_config))
         .route("/api/parse-comments", post(parse_comments))
+        .route("/api/parse-comments-tree", post(parse_comments_tree))
+        .route("/api/export-html", post(export_html))
         .route("/api/insert-wrapped-comment", post(insert_wrapped_comment))
         .route("/api/insert-nextline-comment", post(insert_nextline_comment))
         .route("/api/remove-comment", post(remove_comment))
         .route("/api/quit", post(quit))
         // Web mode indicator
         .route("/api/is-web-mode", get(is_web_mode))
+        // Operator approval for out-of-allowlist writes
+        .route("/api/approvals", get(list_approvals))
+        .route("/api/approve/{approval_id}", post(approve_write))
+        // PTY terminal, streamed over a WebSocket
+        .route("/api/pty/{session_id}", get(pty_ws))
+        // Live file-change notifications
+        .route("/api/events", get(events))
+        // Prometheus metrics (unauthenticated scrape endpoint)
+        .route("/metrics", get(serve_metrics))
+        .layer(middleware::from_fn(require_bearer_token))
+        .layer(middleware::from_fn(enforce_slow_request_timeout))
+        .layer(middleware::from_fn(track_request_metrics))
+        .layer(CompressionLayer::new())
         .layer(cors)
         .layer(Extension(state))
 }
 
+/// GET /metrics - Prometheus text-format exposition
+async fn serve_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::encode(),
+    )
+}
+
+/// Records per-endpoint request counts and latencies.
+async fn track_request_metrics(req: Request, next: Next) -> Response<Body> {
+    // The matched route pattern (e.g. "/api/pty/{session_id}"), not the
+    // literal request path: /api/pty/{session_id} and
+    // /api/approve/{approval_id} embed a fresh UUID per request, which would otherwise grow these
+    // Prometheus label sets without bound over the process's lifetime.
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let method = req.method().to_string();
+
+    let start = Instant::now();
+    let response = next.run(req).await;
+
+    crate::metrics::HTTP_REQUEST_DURATION_SECONDS
+        .with_label_values(&[&route, &method])
+        .observe(start.elapsed().as_secs_f64());
+    crate::metrics::HTTP_REQUESTS_TOTAL
+        .with_label_values(&[&route, &method, response.status().as_str()])
+        .inc();
+
+    response
+}
+
+/// Aborts requests that run longer than `WebState::slow_request_timeout`
+/// with `408 Request Timeout`, so a stuck client over a flaky tunnel can't
+/// hold a connection open forever.
+///
+/// `/api/write-file` is exempt: an out-of-allowlist write can block on
+/// operator approval for up to the 120s window `write_file` passes to
+/// `ApprovalRegistry::wait`, and this blanket deadline would otherwise fire first and
+/// swallow the Denied/Timeout/Cancelled distinction that endpoint returns.
+/// `/api/pty/` is exempt too, since a terminal session is long-lived by
+/// design, not stuck.
+async fn enforce_slow_request_timeout(req: Request, next: Next) -> Response<Body> {
+    let path = req.uri().path().to_string();
+    if path == "/api/write-file" || path.starts_with("/api/pty/") {
+        return next.run(req).await;
+    }
+
+    let timeout = req
+        .extensions()
+        .get::<Arc<WebState>>()
+        .map(|s| s.slow_request_timeout)
+        .unwrap_or(Duration::from_secs(30));
+
+    match tokio::time::timeout(timeout, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => (
+            StatusCode::REQUEST_TIMEOUT,
+            "request exceeded the slow-request deadline",
+        )
+            .into_response(),
+    }
+}
+
+/// Tower middleware enforcing `Authorization: Bearer <token>` on `/api/*`.
+///
+/// Static assets and `/` are left open so the frontend can load before it
+/// has learned the token (e.g. from a `#token=` URL fragment). Every
+/// `/api/*` route is covered, including `/api/approvals` and
+/// `/api/approve/{id}` — once a `--tunnel` is active, inbound connections
+/// are bridged to `127.0.0.1` before reaching axum (see
+/// `LocaltunnelClient::serve_one_connection`), so a remote request is
+/// indistinguishable from a local one and can't be waved through just
+/// because it hit this host.
+async fn require_bearer_token(req: Request, next: Next) -> Response<Body> {
+    let path = req.uri().path().to_string();
+    if !path.starts_with("/api/") {
+        return next.run(req).await;
+    }
+
+    let Some(state) = req.extensions().get::<Arc<WebState>>().cloned() else {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "missing web state").into_response();
+    };
+
+    // Auth is only enabled for `--tunnel`/`--auth` sessions (see
+    // `start_server`); a plain local `--web` session has no token to check.
+    let Some(expected) = &state.auth_token else {
+        return next.run(req).await;
+    };
+
+    // Browser `WebSocket` clients can't set an `Authorization` header on the
+    // upgrade request, so the token is also accepted as a query parameter
+    // (`?token=...`), on the PTY route and every other `/api/*` route alike.
+    let query_token = req.uri().query().and_then(|q| {
+        q.split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(|v| v.to_string())
+    });
+
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|token| token == expected)
+        .or_else(|| query_token.map(|token| &token == expected))
+        .unwrap_or(false);
+
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "missing or invalid bearer token").into_response();
+    }
+
+    next.run(req).await
+}
+
 /// Serve index.html
 async fn serve_index() -> impl IntoResponse {
     match Assets::get("index.html") {
@@ -180,38 +378,207 @@ async fn get_current_file(Extension(state): Extension<Arc<WebState>>) -> impl In
     Json(path)
 }
 
+/// GET /api/file-list
+async fn get_file_list(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
+    let list = state
+        .app_state
+        .file_list
+        .lock()
+        .map(|list| list.iter().map(|p| p.to_string_lossy().to_string()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    Json(list)
+}
+
+/// POST /api/next-file: advance the session's current file and return its
+/// path, or `null` if the session has no file queue.
+async fn next_file(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
+    let path = advance_file(&state.app_state, 1);
+    if let Some(p) = &path {
+        rewatch(&state.watcher, Some(&PathBuf::from(p)));
+    }
+    Json(path)
+}
+
+/// POST /api/prev-file: move the session's current file back and return its
+/// path, or `null` if the session has no file queue.
+async fn prev_file(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
+    let path = advance_file(&state.app_state, -1);
+    if let Some(p) = &path {
+        rewatch(&state.watcher, Some(&PathBuf::from(p)));
+    }
+    Json(path)
+}
+
+/// Shared `next`/`prev` logic: move `file_index` by `delta` (clamped to the
+/// list bounds), update `current_file` to match, and return the new path.
+fn advance_file(app_state: &AppState, delta: i64) -> Option<String> {
+    let list = app_state.file_list.lock().ok()?.clone();
+    if list.is_empty() {
+        return None;
+    }
+    let mut index = app_state.file_index.lock().ok()?;
+    let next_index = (*index as i64 + delta).clamp(0, list.len() as i64 - 1);
+    *index = next_index as usize;
+    let path = list[*index].clone();
+    if let Ok(mut current) = app_state.current_file.lock() {
+        *current = Some(path.clone());
+    }
+    Some(path.to_string_lossy().to_string())
+}
+
 /// POST /api/read-file
 async fn read_file(
+    Extension(state): Extension<Arc<WebState>>,
     Json(req): Json<ReadFileRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    std::fs::read_to_string(&req.path)
+    let path = resolve_request_path(&state, &req.path).map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    std::fs::read_to_string(&path)
         .map(Json)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
+/// Resolve a client-supplied path against `state.app_state.root_dir`, if
+/// one is configured, rejecting any attempt to escape it.
+fn resolve_request_path(state: &WebState, req_path: &str) -> Result<PathBuf, String> {
+    match &state.app_state.root_dir {
+        Some(root) => resolve_within_root(root, req_path),
+        None => Ok(PathBuf::from(req_path)),
+    }
+}
+
+/// Resolve every path in `files` the same way [`resolve_request_path`]
+/// resolves an incoming write request, so `is_allowlisted`'s `starts_with`
+/// comparison isn't defeated by a representation difference between the
+/// two — a symlinked project directory, or a relative CLI argument — that
+/// would otherwise send an ordinary write to the session's own file down
+/// the operator-approval path.
+fn canonicalize_allowlist(files: Vec<PathBuf>, root_dir: Option<&std::path::Path>) -> Vec<PathBuf> {
+    files
+        .into_iter()
+        .map(|path| match root_dir {
+            Some(root) => resolve_within_root(root, &path.to_string_lossy()).unwrap_or(path),
+            None => path,
+        })
+        .collect()
+}
+
 /// POST /api/write-file
 async fn write_file(
+    Extension(state): Extension<Arc<WebState>>,
     Json(req): Json<WriteFileRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    std::fs::write(&req.path, &req.content)
+    let path = resolve_request_path(&state, &req.path).map_err(|e| (StatusCode::FORBIDDEN, e))?;
+
+    if !is_allowlisted(&path, &state.write_allowlist) {
+        let (id, rx) = state.approvals.request(&path);
+        tracing::info!(
+            "write to {} requires operator approval (id: {})",
+            path.display(),
+            id
+        );
+        match state.approvals.wait(&id, rx, 120).await {
+            Ok(()) => {}
+            Err(ApprovalError::Denied) => {
+                return Err((StatusCode::FORBIDDEN, "write denied by operator".to_string()))
+            }
+            Err(ApprovalError::Timeout) => {
+                return Err((
+                    StatusCode::REQUEST_TIMEOUT,
+                    "operator did not respond in time".to_string(),
+                ))
+            }
+            Err(ApprovalError::Cancelled) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "approval was cancelled".to_string(),
+                ))
+            }
+        }
+    }
+
+    std::fs::write(&path, &req.content)
         .map(|_| Json(()))
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
+/// GET /api/approvals - list writes currently blocked on operator approval,
+/// so the desktop UI has something to poll and render besides the
+/// `tracing::info!` line logged when one is opened.
+async fn list_approvals(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
+    Json(state.approvals.list())
+}
+
+/// POST /api/approve/{approval_id} - operator resolves a pending write
+async fn approve_write(
+    Extension(state): Extension<Arc<WebState>>,
+    Path(approval_id): Path<String>,
+    Json(req): Json<ApproveRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let decision = match req.decision.as_str() {
+        "approve" => ApprovalDecision::Approved,
+        "deny" => ApprovalDecision::Denied,
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("unknown decision: {}", other),
+            ))
+        }
+    };
+
+    if state.approvals.resolve(&approval_id, decision) {
+        Ok(Json(()))
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            "no pending approval with that id".to_string(),
+        ))
+    }
+}
+
+/// GET /api/pty/{session_id} - upgrade to a WebSocket-backed PTY terminal
+async fn pty_ws(
+    ws: WebSocketUpgrade,
+    Extension(state): Extension<Arc<WebState>>,
+    Path(session_id): Path<String>,
+    Query(params): Query<CreatePtyParams>,
+) -> impl IntoResponse {
+    let registry = state.pty_sessions.clone();
+    ws.on_upgrade(move |socket| handle_pty_socket(socket, registry, session_id, params))
+}
+
 /// POST /api/set-current-file
 async fn set_current_file(
     Extension(state): Extension<Arc<WebState>>,
     Json(req): Json<SetCurrentFileRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let mut current = state
-        .app_state
-        .current_file
-        .lock()
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    *current = Some(PathBuf::from(req.path));
+    let path = resolve_request_path(&state, &req.path).map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    {
+        let mut current = state
+            .app_state
+            .current_file
+            .lock()
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        *current = Some(path.clone());
+    }
+    rewatch(&state.watcher, Some(&path));
     Ok(Json(()))
 }
 
+/// GET /api/events - Server-Sent-Events stream of file-changed/file-removed
+async fn events(
+    Extension(state): Extension<Arc<WebState>>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let rx = state.watcher.subscribe();
+    let stream = BroadcastStream::new(rx).filter_map(|msg| match msg {
+        Ok(FileEvent::Changed { content }) => {
+            Some(Ok(SseEvent::default().event("file-changed").data(content)))
+        }
+        Ok(FileEvent::Removed) => Some(Ok(SseEvent::default().event("file-removed").data(""))),
+        Err(_) => None,
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 /// GET /api/is-stdin-mode
 async fn is_stdin_mode(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
     Json(state.app_state.stdin_mode)
@@ -231,15 +598,32 @@ async fn post_config(Json(config): Json<AppConfig>) -> Result<impl IntoResponse,
 
 /// POST /api/parse-comments
 async fn parse_comments(Json(req): Json<ParseCommentsRequest>) -> impl IntoResponse {
-    let comments = crate::comments::parse_comments(req.content);
+    let comments = crate::comments::parse_comments(req.content, req.file_name);
     Json(comments)
 }
 
+/// POST /api/parse-comments-tree
+async fn parse_comments_tree(Json(req): Json<ParseCommentsRequest>) -> impl IntoResponse {
+    let comments = crate::comments::parse_comments_tree(req.content, req.file_name);
+    Json(comments)
+}
+
+/// POST /api/export-html
+async fn export_html(Json(req): Json<ParseCommentsRequest>) -> impl IntoResponse {
+    Html(crate::comments::export_html(req.content, req.file_name))
+}
+
 /// POST /api/insert-wrapped-comment
 async fn insert_wrapped_comment(
     Json(req): Json<InsertWrappedCommentRequest>,
 ) -> impl IntoResponse {
-    let (content, id) = insert_wrapped_comment_internal(req.content, req.start_pos, req.end_pos, req.text);
+    let (content, id) = insert_wrapped_comment_internal(
+        req.content,
+        req.start_pos,
+        req.end_pos,
+        req.text,
+        req.file_name,
+    );
     Json(InsertCommentResponse { content, id })
 }
 
@@ -247,13 +631,19 @@ async fn insert_wrapped_comment(
 async fn insert_nextline_comment(
     Json(req): Json<InsertNextlineCommentRequest>,
 ) -> impl IntoResponse {
-    let (content, id) = insert_nextline_comment_internal(req.content, req.line_start_pos, req.line_end_pos, req.text);
+    let (content, id) = insert_nextline_comment_internal(
+        req.content,
+        req.line_start_pos,
+        req.line_end_pos,
+        req.text,
+        req.file_name,
+    );
     Json(InsertCommentResponse { content, id })
 }
 
 /// POST /api/remove-comment
 async fn remove_comment(Json(req): Json<RemoveCommentRequest>) -> impl IntoResponse {
-    let content = remove_comment_internal(req.content, req.comment_id);
+    let content = remove_comment_internal(req.content, req.comment_id, req.file_name);
     Json(content)
 }
 
@@ -262,15 +652,42 @@ async fn is_web_mode() -> impl IntoResponse {
     Json(true)
 }
 
-/// POST /api/quit - Triggers shutdown and returns final report
+/// POST /api/quit - Triggers graceful shutdown and returns final report
 async fn quit(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
     let app_state = &state.app_state;
+    let served_secs = state.started_at.elapsed().as_secs_f64();
+
+    // A multi-file/directory session reports every file's comments at
+    // once, keyed by path, rather than just whichever file was open last.
+    let session_files = app_state.file_list.lock().map(|l| l.clone()).unwrap_or_default();
+    if !app_state.silent && session_files.len() > 1 {
+        let files = collect_file_comments(&session_files);
+        let comments_count: usize = files.iter().map(|f| f.comments.len()).sum();
+
+        let output_str = if app_state.json_output {
+            format_comments_json_multi(&files)
+        } else {
+            format_comments_readable_multi(&files)
+        };
+
+        println!("{}", output_str);
+
+        let response = QuitResponse {
+            success: true,
+            output: output_str,
+            comments_count,
+            served_secs,
+        };
+
+        let _ = state.shutdown_tx.send(());
+        return Json(response);
+    }
 
     // Generate output based on current file state
     if !app_state.silent {
         if let Some(file_path) = app_state.current_file.lock().ok().and_then(|f| f.clone()) {
             if let Ok(content) = std::fs::read_to_string(&file_path) {
-                let comments = parse_comments_for_output(&content);
+                let comments = parse_comments_for_output(&content, file_path.to_str());
                 let comments_count = comments.len();
 
                 let output_str = if app_state.stdin_mode {
@@ -289,9 +706,9 @@ async fn quit(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
                     }
                 } else if !comments.is_empty() {
                     if app_state.json_output {
-                        format_comments_json(&comments)
+                        format_comments_json(&content, &comments)
                     } else {
-                        format_comments_readable(&comments)
+                        format_comments_readable(&content, &comments)
                     }
                 } else {
                     String::new()
@@ -306,14 +723,13 @@ async fn quit(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
                     success: true,
                     output: output_str,
                     comments_count,
+                    served_secs,
                 };
 
-                // Trigger shutdown
-                if let Ok(mut shutdown_tx) = state.shutdown_tx.lock() {
-                    if let Some(tx) = shutdown_tx.take() {
-                        let _ = tx.send(());
-                    }
-                }
+                // Trigger graceful shutdown; the serving task drains
+                // in-flight connections before this request's own response
+                // has a chance to be dropped.
+                let _ = state.shutdown_tx.send(());
 
                 return Json(response);
             }
@@ -321,41 +737,108 @@ async fn quit(Extension(state): Extension<Arc<WebState>>) -> impl IntoResponse {
     }
 
     // No file loaded or silent mode
-    if let Ok(mut shutdown_tx) = state.shutdown_tx.lock() {
-        if let Some(tx) = shutdown_tx.take() {
-            let _ = tx.send(());
-        }
-    }
+    let _ = state.shutdown_tx.send(());
 
     Json(QuitResponse {
         success: true,
         output: String::new(),
         comments_count: 0,
+        served_secs,
     })
 }
 
 /// Start the web server on the specified port
+///
+/// Returns a receiver that resolves once the server has gracefully
+/// drained and fully shut down (triggered by `/api/quit`), and the bearer
+/// token that must accompany every `/api/*` request when `require_auth` is
+/// set (see [`require_bearer_token`]); `None` when it isn't.
+///
+/// `require_auth` should be set whenever the server's reachable beyond this
+/// machine (a `--tunnel` session) or was explicitly requested via `--auth`;
+/// a plain local `--web` session can leave it unset.
+///
+/// `slow_request_timeout_secs` sets [`WebState::slow_request_timeout`] (see
+/// `--slow-request-timeout`); the deadline is long enough to matter for
+/// operator-approval waits (`write_file`) and interactive PTY sessions,
+/// both of which are exempted from it outright rather than tuned via this
+/// value (see `enforce_slow_request_timeout`).
+#[allow(clippy::type_complexity)]
 pub async fn start_server(
     port: u16,
     app_state: Arc<AppState>,
-) -> Result<oneshot::Receiver<()>, Box<dyn std::error::Error + Send + Sync>> {
-    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    require_auth: bool,
+    slow_request_timeout_secs: u64,
+) -> Result<
+    (oneshot::Receiver<()>, Option<String>, Arc<RwLock<Vec<String>>>),
+    Box<dyn std::error::Error + Send + Sync>,
+> {
+    let (shutdown_tx, _) = broadcast::channel::<()>(1);
+    let auth_token = require_auth.then(generate_token);
+    // The whole session's file queue may be written without approval, not
+    // just whichever one is current when the server starts.
+    let write_allowlist = app_state
+        .file_list
+        .lock()
+        .map(|list| list.clone())
+        .unwrap_or_default();
+    let write_allowlist = if write_allowlist.is_empty() {
+        app_state
+            .current_file
+            .lock()
+            .ok()
+            .and_then(|f| f.clone())
+            .into_iter()
+            .collect::<Vec<_>>()
+    } else {
+        write_allowlist
+    };
+    let write_allowlist = canonicalize_allowlist(write_allowlist, app_state.root_dir.as_deref());
+
+    let watcher = FileWatcher::new();
+    if let Some(initial_file) = write_allowlist.first() {
+        watcher.watch(initial_file);
+    }
+
+    let cors_origins = Arc::new(RwLock::new(load_config_internal().cors_origins));
 
     let web_state = Arc::new(WebState {
         app_state,
-        shutdown_tx: Mutex::new(Some(shutdown_tx)),
+        shutdown_tx: shutdown_tx.clone(),
+        auth_token: auth_token.clone(),
+        write_allowlist,
+        approvals: ApprovalRegistry::new(),
+        pty_sessions: Arc::new(PtyRegistry::new()),
+        watcher,
+        started_at: Instant::now(),
+        slow_request_timeout: Duration::from_secs(slow_request_timeout_secs),
+        cors_origins: cors_origins.clone(),
     });
 
     let app = create_router(web_state);
 
     let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
 
-    println!("Web server running at http://127.0.0.1:{}", port);
+    tracing::info!("Web server running at http://127.0.0.1:{}", port);
+    match &auth_token {
+        Some(token) => tracing::info!("Bearer token (required for all API requests): {}", token),
+        None => tracing::info!("Auth disabled (local session); pass --auth or --tunnel to require a token"),
+    }
+
+    let (done_tx, done_rx) = oneshot::channel::<()>();
+    let mut graceful_signal = shutdown_tx.subscribe();
 
-    // Spawn server in background
+    // Spawn server in background, draining in-flight connections on
+    // shutdown instead of being dropped when the process exits.
     tokio::spawn(async move {
-        axum::serve(listener, app).await.ok();
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move {
+                let _ = graceful_signal.recv().await;
+            })
+            .await
+            .ok();
+        let _ = done_tx.send(());
     });
 
-    Ok(shutdown_rx)
+    Ok((done_rx, auth_token, cors_origins))
 }